@@ -0,0 +1,51 @@
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use sonai_metrics::{TextMetricFactory, features_from_metrics};
+use std::hint::black_box;
+
+// a comment, a devlog and a book chapter
+const SHORT: &str = include_str!("../tests/golden/human_casual.md");
+
+fn medium() -> String {
+    [
+        include_str!("../tests/golden/ai_markdown.md"),
+        include_str!("../tests/golden/code_heavy.md"),
+        include_str!("../tests/golden/human_casual.md"),
+        include_str!("../tests/golden/readme_template.md"),
+        include_str!("../tests/golden/unicode_paste.md"),
+    ]
+    .join("\n\n")
+}
+
+fn long() -> String {
+    vec![medium(); 100].join("\n\n")
+}
+
+fn factory(c: &mut Criterion) {
+    c.bench_function("TextMetricFactory::new", |b| {
+        b.iter(|| TextMetricFactory::new().unwrap())
+    });
+}
+
+fn calculate(c: &mut Criterion) {
+    let factory = TextMetricFactory::new().unwrap();
+    let mut group = c.benchmark_group("calculate");
+
+    for (name, text) in [("short", SHORT.to_string()), ("medium", medium()), ("long", long())] {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_function(name, |b| b.iter(|| factory.calculate(black_box(&text))));
+    }
+
+    group.finish();
+}
+
+fn features(c: &mut Criterion) {
+    let factory = TextMetricFactory::new().unwrap();
+    let metrics = factory.calculate(&medium());
+
+    c.bench_function("features_from_metrics", |b| {
+        b.iter(|| features_from_metrics(black_box(&[&metrics])))
+    });
+}
+
+criterion_group!(benches, factory, calculate, features);
+criterion_main!(benches);
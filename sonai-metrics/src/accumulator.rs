@@ -0,0 +1,754 @@
+use crate::ngram::Scorer;
+use crate::phrases::{PhraseMetric, PhraseScanner};
+use crate::spelling::Informal;
+use crate::{
+    InputFormat, TextMetricFactory, TextMetrics, emoji, html, markdown, normalize, readability,
+    repetition, stylometry, template, tokens,
+};
+use pulldown_cmark::{BrokenLink, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Computes [`TextMetrics`] over text that arrives in pieces, get one from
+/// [`TextMetricFactory::accumulator`].
+///
+/// Markdown and plain text are parsed one block at a time, blocks end at blank lines outside code
+/// fences and HTML blocks. A block that links to a reference definition that hasn't been seen yet
+/// waits, with everything after it, until the definition shows up or the text ends. The prose left
+/// after parsing is measured as it comes, so memory stays bounded by the longest block and
+/// sentence. HTML is buffered until [`MetricAccumulator::finish`].
+///
+/// The result is the same as measuring the whole text at once, however it was split into chunks.
+pub struct MetricAccumulator<'a> {
+    factory: &'a TextMetricFactory,
+
+    pending: String,
+    scanned: usize,
+    previous_blank: bool,
+    open: Option<Open>,
+
+    // parsed blocks waiting for a reference definition, only for markdown
+    held: VecDeque<String>,
+    definitions: HashMap<String, (String, String)>,
+    waiting: bool,
+
+    suspicious: normalize::Suspicious,
+    human_scorer: Scorer<'a>,
+    ai_scorer: Scorer<'a>,
+
+    html_escapes: usize,
+    markdown: usize,
+    profile: markdown::MarkdownProfile,
+    emoji_profile: emoji::EmojiProfile,
+    inline_code: usize,
+    code_chars: usize,
+    all_chars: usize,
+    tokenizer: tokens::Tokenizer,
+    line: LineLabel,
+
+    // tokenized prose that hasn't been measured yet
+    prose: String,
+    sentence: String,
+    last_char: Option<char>,
+
+    sentences: usize,
+    noncap_sentences: usize,
+    sentence_lengths: stylometry::MeanVariance,
+    lexical: stylometry::LexicalDiversity,
+    punctuation: stylometry::Punctuation,
+    readability: readability::ReadabilityCounts,
+    labels: usize,
+    template_sections: usize,
+    misspellings: usize,
+    elongations: usize,
+    missing_apostrophes: usize,
+    texting: usize,
+
+    emoji_count: usize,
+    irr_dash: usize,
+    irr_quote: usize,
+    irr_arr: usize,
+    phrases: PhraseScanner<'a>,
+
+    repetition: repetition::Repetition,
+    compression: repetition::Compression,
+}
+
+/// A construct that can contain blank lines, nothing inside it starts a block.
+#[derive(Clone, Copy, PartialEq)]
+enum Open {
+    Fence(char, usize),
+    // closed by the first line containing this
+    Html(&'static str),
+}
+
+/// Length of the code fence opening or closing `line`, if it is one.
+fn fence(line: &str) -> Option<Open> {
+    let line = line.trim_start();
+    let marker = line.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let len = line.chars().take_while(|&c| c == marker).count();
+
+    (len >= 3).then_some(Open::Fence(marker, len))
+}
+
+/// HTML blocks that only end at their closing tag, even across blank lines.
+fn html_block(line: &str) -> Option<Open> {
+    let line = line.trim_start().to_ascii_lowercase();
+
+    let end = if line.starts_with("<!--") {
+        "-->"
+    } else if line.starts_with("<![cdata[") {
+        "]]>"
+    } else if line.starts_with("<?") {
+        "?>"
+    } else if line.starts_with("<!") {
+        ">"
+    } else {
+        ["pre", "script", "style", "textarea"]
+            .into_iter()
+            .find(|tag| {
+                line.strip_prefix('<')
+                    .and_then(|rest| rest.strip_prefix(tag))
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '>', '\t']))
+            })
+            .map(|tag| match tag {
+                "pre" => "</pre>",
+                "script" => "</script>",
+                "style" => "</style>",
+                _ => "</textarea>",
+            })?
+    };
+
+    // a comment that closes on its opening line isn't open
+    (!line[2..].contains(end)).then_some(Open::Html(end))
+}
+
+/// Reference labels match case-insensitively and with any run of whitespace as a single space.
+fn label_key(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Position of the last space or newline in `text` that directly follows a non-whitespace char,
+/// words and graphemes never span one. 0 if there is none.
+fn last_boundary(text: &str) -> usize {
+    let mut after = None;
+
+    for (i, c) in text.char_indices().rev() {
+        if !c.is_whitespace()
+            && let Some(boundary) = after
+        {
+            return boundary;
+        }
+
+        after = matches!(c, ' ' | '\n').then_some(i);
+    }
+
+    0
+}
+
+/// `Label:` lines, fed the text of the blocks a char at a time. Only what's before the first `:`
+/// of a line matters.
+#[derive(Default)]
+struct LineLabel {
+    colon: bool,
+    // anything but whitespace after the first colon
+    after: bool,
+    nonblank: bool,
+    // anything but letters and whitespace before it
+    other: bool,
+    name: template::SectionName,
+}
+
+impl LineLabel {
+    fn push(&mut self, c: char) {
+        if self.colon {
+            self.after |= !c.is_whitespace();
+        } else if c == ':' {
+            self.colon = true;
+        } else {
+            self.name.push(c);
+            self.nonblank |= !c.is_whitespace();
+            self.other |= !c.is_alphabetic() && !c.is_whitespace();
+        }
+    }
+
+    /// (is a template section, is a label), the line is reset for the next one.
+    fn finish(&mut self) -> (bool, bool) {
+        let line = std::mem::take(self);
+
+        if !line.colon {
+            return (false, false);
+        }
+
+        // Tech Stack: React, Tailwind
+        let template = line.name.is_template_section();
+
+        let label = !line.after
+            && line.nonblank
+            && !line.other
+            && !matches!(line.name.as_str(), Some("http" | "https"));
+
+        (template, label)
+    }
+}
+
+impl<'a> MetricAccumulator<'a> {
+    pub(crate) fn new(factory: &'a TextMetricFactory) -> Self {
+        let models = &factory.language_models;
+
+        Self {
+            factory,
+            pending: String::new(),
+            scanned: 0,
+            previous_blank: false,
+            open: None,
+            held: VecDeque::new(),
+            definitions: HashMap::new(),
+            waiting: false,
+            suspicious: normalize::Suspicious::default(),
+            human_scorer: models.human.scorer(),
+            ai_scorer: models.ai.scorer(),
+            html_escapes: 0,
+            markdown: 0,
+            profile: markdown::MarkdownProfile::default(),
+            emoji_profile: emoji::EmojiProfile::new(),
+            inline_code: 0,
+            code_chars: 0,
+            all_chars: 0,
+            tokenizer: tokens::Tokenizer::default(),
+            prose: String::new(),
+            sentence: String::new(),
+            line: LineLabel::default(),
+            last_char: None,
+            sentences: 0,
+            noncap_sentences: 0,
+            sentence_lengths: stylometry::MeanVariance::default(),
+            lexical: stylometry::LexicalDiversity::default(),
+            punctuation: stylometry::Punctuation::default(),
+            readability: readability::ReadabilityCounts::default(),
+            labels: 0,
+            template_sections: 0,
+            misspellings: 0,
+            elongations: 0,
+            missing_apostrophes: 0,
+            texting: 0,
+            emoji_count: 0,
+            irr_dash: 0,
+            irr_quote: 0,
+            irr_arr: 0,
+            phrases: factory.phrases.scanner(),
+            repetition: repetition::Repetition::default(),
+            compression: repetition::Compression::default(),
+        }
+    }
+
+    pub fn push(&mut self, chunk: &str) {
+        self.pending.push_str(chunk);
+
+        if self.factory.input_format != InputFormat::Html {
+            self.measure_complete_blocks();
+        }
+    }
+
+    /// Pushes everything `reader` has left.
+    pub fn read_from<R: BufRead>(&mut self, mut reader: R) -> io::Result<()> {
+        let mut line = String::new();
+
+        while reader.read_line(&mut line)? > 0 {
+            self.push(&line);
+            line.clear();
+        }
+
+        Ok(())
+    }
+
+    /// A block starts at a line after a blank line, unless it's indented (list item continuation,
+    /// indented code) or inside a fence or HTML block.
+    fn measure_complete_blocks(&mut self) {
+        while let Some(newline) = self.pending[self.scanned..].find('\n') {
+            let line_start = self.scanned;
+            let line = &self.pending[line_start..line_start + newline];
+            self.scanned += newline + 1;
+
+            let blank = line.trim().is_empty();
+            let starts_block = self.previous_blank
+                && self.open.is_none()
+                && !blank
+                && !line.starts_with([' ', '\t']);
+            self.previous_blank = blank;
+
+            self.open = match (self.open, fence(line)) {
+                (None, Some(open)) => Some(open),
+                (Some(Open::Fence(open, open_len)), Some(Open::Fence(marker, len)))
+                    if open == marker && len >= open_len =>
+                {
+                    None
+                }
+                (Some(Open::Html(end)), _) if line.to_ascii_lowercase().contains(end) => None,
+                (None, None) => html_block(line),
+                (open, _) => open,
+            };
+
+            if starts_block {
+                let rest = self.pending.split_off(line_start);
+                let block = std::mem::replace(&mut self.pending, rest);
+                self.scanned -= line_start;
+                self.measure(&block);
+            }
+        }
+    }
+
+    /// Counts the suspicious characters in `text` and cleans them up, everything after this runs
+    /// on normalized text, so zero-width spaces can't hide buzzwords.
+    fn normalize(&mut self, text: &str) -> String {
+        let suspicious = normalize::suspicious_characters(text);
+        self.suspicious.invisible += suspicious.invisible;
+        self.suspicious.unusual_whitespace += suspicious.unusual_whitespace;
+        self.suspicious.confusables += suspicious.confusables;
+
+        let normalized = normalize::normalize(text);
+
+        self.human_scorer.feed(&normalized);
+        self.ai_scorer.feed(&normalized);
+
+        self.markdown += normalized.matches(['•', '●']).count(); // Lists are OK, this shit is not
+
+        normalized
+    }
+
+    fn measure(&mut self, text: &str) {
+        // existing markdown vs non-markdown
+
+        match self.factory.input_format {
+            // entities are expected in html, only ones that survive decoding are artifacts. The
+            // text is normalized after decoding, or &zwj; and &nbsp; would get past it
+            InputFormat::Html => {
+                let html = html::parse(text);
+                self.html_escapes += html.escape_artifacts;
+
+                let events: Vec<_> = html
+                    .events
+                    .into_iter()
+                    .map(|event| match event {
+                        Event::Text(text) => Event::Text(self.normalize(&text).into()),
+                        Event::Code(code) => Event::Code(self.normalize(&code).into()),
+                        event => event,
+                    })
+                    .collect();
+
+                self.observe(events);
+            }
+            InputFormat::Plain => {
+                let text = self.normalize(text);
+                // slow but fine, only one.
+                self.html_escapes += text.matches("&amp;").count();
+                self.observe([Event::Text(text.into())]);
+            }
+            InputFormat::Markdown => {
+                let text = self.normalize(text);
+                self.html_escapes += text.matches("&amp;").count();
+                self.define_references(&text);
+                self.held.push_back(text);
+                self.parse_held(false);
+            }
+        }
+    }
+
+    /// Remembers the reference definitions in `block`, the first definition of a label wins.
+    fn define_references(&mut self, block: &str) {
+        if !block.contains("]:") {
+            return;
+        }
+
+        let parser = Parser::new_ext(block, Options::ENABLE_TABLES);
+
+        for (label, definition) in parser.reference_definitions().iter() {
+            let label = label_key(label);
+
+            if let Entry::Vacant(entry) = self.definitions.entry(label) {
+                let title = definition.title.as_deref().unwrap_or_default();
+                entry.insert((definition.dest.to_string(), title.to_string()));
+                // whatever is waiting might have been waiting for this one
+                self.waiting = false;
+            }
+        }
+    }
+
+    /// Parses the held blocks in order, stopping at one that links to a reference that isn't
+    /// defined yet unless the text is over. A whole document parse would see definitions that come
+    /// after the link.
+    fn parse_held(&mut self, end: bool) {
+        while !self.waiting || end {
+            let Some(block) = self.held.pop_front() else {
+                return;
+            };
+
+            let mut undefined = false;
+            let definitions = &self.definitions;
+            let callback = |link: BrokenLink| {
+                let definition = definitions.get(&label_key(&link.reference));
+                undefined |= definition.is_none();
+
+                definition.map(|(dest, title)| {
+                    (
+                        CowStr::from(dest.to_string()),
+                        CowStr::from(title.to_string()),
+                    )
+                })
+            };
+
+            let events: Vec<Event> = Parser::new_with_broken_link_callback(
+                &block,
+                Options::ENABLE_TABLES,
+                Some(callback),
+            )
+            .collect();
+
+            if undefined && !end {
+                drop(events);
+                self.held.push_front(block);
+                self.waiting = true;
+                return;
+            }
+
+            self.observe(events);
+        }
+    }
+
+    fn observe<'e>(&mut self, events: impl IntoIterator<Item = Event<'e>>) {
+        let factory = self.factory;
+        let mut in_code_block = false;
+        let mut heading: Option<String> = None;
+
+        for event in events {
+            self.profile.observe(&event);
+            self.emoji_profile
+                .observe(&event, &factory.ai_emojis, &factory.human_emojis);
+
+            if matches!(
+                event,
+                Event::Rule
+                    | Event::Start(
+                        Tag::BlockQuote(_)
+                            | Tag::Emphasis
+                            | Tag::Subscript
+                            | Tag::Superscript
+                            | Tag::Strong
+                            | Tag::Strikethrough
+                            | Tag::Heading { .. }
+                            | Tag::Link { .. }
+                            | Tag::Image { .. }
+                    )
+            ) {
+                self.markdown += 1;
+            }
+
+            match &event {
+                Event::Start(Tag::Heading { .. }) => heading = Some(String::new()),
+                Event::Text(t) => {
+                    if let Some(heading) = &mut heading {
+                        heading.push_str(t);
+                    }
+                }
+                Event::End(TagEnd::Heading(_)) => {
+                    let heading = heading.take().unwrap_or_default();
+                    self.template_sections += usize::from(template::is_template_section(&heading));
+                }
+                _ => {}
+            }
+
+            // the prose of consecutive blocks runs together, the deployed model was trained on
+            // that. Label lines still end with their block.
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                Event::Text(t) if !in_code_block => {
+                    self.label_text(&t);
+                    self.cleaned(&t);
+                }
+                Event::Text(t) => {
+                    self.code_chars += t.chars().filter(|c| !c.is_whitespace()).count()
+                }
+                Event::Code(c) => {
+                    self.inline_code += 1;
+                    self.code_chars += c.chars().filter(|c| !c.is_whitespace()).count();
+                }
+                Event::SoftBreak | Event::HardBreak if !in_code_block => {
+                    self.line.push(' ');
+                    self.cleaned(" ");
+                }
+                Event::End(TagEnd::TableCell) => {
+                    self.line.push(' ');
+                    self.cleaned(" ");
+                }
+                Event::End(TagEnd::TableHead | TagEnd::TableRow) => {
+                    self.end_line();
+                    self.cleaned("\n");
+                }
+                Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item) => {
+                    self.end_line()
+                }
+                _ => {}
+            }
+        }
+
+        self.end_line();
+        self.measure_prose(false);
+    }
+
+    /// Prose as the source has it, for the `Label:` lines
+    fn label_text(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                self.end_line();
+            } else {
+                self.line.push(c);
+            }
+        }
+    }
+
+    /// Text left after removing markup and code.
+    fn cleaned(&mut self, text: &str) {
+        // code and urls in prose would otherwise count as dashes, arrows, hashtags and sentence ends
+        self.all_chars += text.chars().filter(|c| !c.is_whitespace()).count();
+        self.tokenizer.feed(text, &mut self.prose);
+    }
+
+    /// Measures the prose up to the last point no word or grapheme can span, or all of
+    /// it at the `end`.
+    fn measure_prose(&mut self, end: bool) {
+        let boundary = if end {
+            self.prose.len()
+        } else {
+            last_boundary(&self.prose)
+        };
+
+        if boundary == 0 {
+            return;
+        }
+
+        let rest = self.prose.split_off(boundary);
+        let text = std::mem::replace(&mut self.prose, rest);
+        let factory = self.factory;
+
+        let mut sentences = text.as_str();
+        while let Some(end) = sentences.find(['.', '!', '?', '\n']) {
+            self.sentence.push_str(&sentences[..end]);
+            self.end_sentence();
+            sentences = &sentences[end + 1..];
+        }
+        self.sentence.push_str(sentences);
+
+        for word in text.unicode_words() {
+            self.lexical.push(word);
+            self.repetition.push_word(word);
+
+            match factory.spelling.classify(word) {
+                Some(Informal::Misspelling) => self.misspellings += 1,
+                Some(Informal::Elongation) => self.elongations += 1,
+                Some(Informal::MissingApostrophe) => self.missing_apostrophes += 1,
+                Some(Informal::Texting) => self.texting += 1,
+                None => {}
+            }
+
+            if word
+                .chars()
+                .all(|c| c.is_alphabetic() || matches!(c, '\'' | '’'))
+            {
+                self.readability.push(word);
+            }
+        }
+
+        self.punctuation.observe(&text);
+
+        for grapheme in text.graphemes(true) {
+            if emojis::get(grapheme).is_some()
+                && !factory.human_emojis.contains(&emoji::emoji_key(grapheme))
+            {
+                self.emoji_count += 1;
+                continue;
+            }
+
+            for c in grapheme.chars() {
+                match c {
+                    '–' | '—' | '‒' | '―' | '⸻' | '⸺' | '−' | '﹘' | '－' | '‑' | '‐' | '᠆'
+                    | '־' | '֊' => self.irr_dash += 1,
+                    '→' | '↑' | '↓' | '↔' | '↕' | '⇒' | '⇐' | '⇑' | '⇓' | '➔' | '➜' => {
+                        self.irr_arr += 1
+                    }
+                    '“' | '”' | '‘' | '’' => self.irr_quote += 1,
+                    // ascii - and -> are what people type, foo-bar and login -> home included
+                    _ => {}
+                }
+            }
+        }
+
+        self.phrases.feed(&text);
+        self.compression.feed(&text);
+
+        if let Some(c) = text.trim_end().chars().next_back() {
+            self.last_char = Some(c);
+        }
+    }
+
+    fn end_sentence(&mut self) {
+        let sentence = std::mem::take(&mut self.sentence);
+        let trimmed = sentence.trim();
+
+        if let Some(first_char) = trimmed.chars().next() {
+            if first_char.is_ascii() && !first_char.is_uppercase() {
+                self.noncap_sentences += 1;
+            }
+
+            self.sentences += 1;
+            let words: Vec<&str> = trimmed.unicode_words().collect();
+            self.sentence_lengths.push(words.len());
+            self.repetition.push_sentence(&words);
+        }
+
+        // keep the allocation for the next sentence
+        self.sentence = sentence;
+        self.sentence.clear();
+    }
+
+    fn end_line(&mut self) {
+        let (template, label) = self.line.finish();
+        self.template_sections += usize::from(template);
+        self.labels += usize::from(label);
+    }
+
+    pub fn finish(mut self) -> TextMetrics {
+        let rest = std::mem::take(&mut self.pending);
+        self.measure(&rest);
+        self.parse_held(true);
+
+        self.tokenizer.finish(&mut self.prose);
+        self.measure_prose(true);
+        self.end_sentence();
+        self.end_line();
+
+        let (perplexity, ai_log_likelihood_ratio) =
+            match (self.human_scorer.finish(), self.ai_scorer.finish()) {
+                (Some((human, n)), Some((ai, m))) => {
+                    let human = human / n as f64;
+                    ((-human).exp2(), ai / m as f64 - human)
+                }
+                _ => (0., 0.),
+            };
+
+        let sentence_count = self.sentences.max(1);
+        let sc = sentence_count as f64;
+
+        let (sentence_length_mean, sentence_length_variance) = self.sentence_lengths.finish();
+        let lexical = self.lexical.finish();
+        let readability = self.readability.finish(sentence_count);
+
+        let code_chars = self.code_chars + self.tokenizer.code_chars;
+        let code_density = code_chars as f64 / (self.code_chars + self.all_chars).max(1) as f64;
+
+        let phrases = &self.phrases;
+
+        let buzzwords = phrases.count(PhraseMetric::Buzzword) as f64
+            - phrases.count(PhraseMetric::NegativeBuzzword) as f64;
+        let backstory = phrases.count(PhraseMetric::Backstory) as f64
+            - phrases.count(PhraseMetric::NegativeBackstory) as f64;
+
+        // fancy can also be interpreted as proper english. trailing commas are NOT proper english
+        let informality = (if self.last_char == Some(',') { 1. } else { 0. })
+            + phrases.count(PhraseMetric::BrokenEnglish) as f64
+            + 1.5 * self.noncap_sentences as f64
+            - phrases.count(PhraseMetric::MrFancyPants) as f64;
+
+        let repeats = self.repetition.finish();
+
+        let profile = &self.profile;
+        let emoji_profile = &self.emoji_profile;
+        let prose = &self.tokenizer;
+
+        TextMetrics {
+            emoji_rate: (self.emoji_count as f64) / sc,
+            buzzword_rate: buzzwords / sc,
+            backstory_count: backstory,
+            incorrect_perspective: phrases.count(PhraseMetric::IncorrectPerspective) as f64 / sc,
+            human_informality: informality / sc,
+
+            devlog_count: phrases.count(PhraseMetric::Devlog) as f64,
+            html_escape_count: self.html_escapes as f64,
+            not_just_count: phrases.count(PhraseMetric::NotJust) as f64,
+
+            irregular_quotations: (self.irr_quote as f64) / sc,
+            irregular_dashes: self.irr_dash as f64,
+            irregular_arrows: self.irr_arr as f64,
+            irregular_ellipsis: phrases.count(PhraseMetric::Ellipsis) as f64,
+            irregular_markdown: self.markdown as f64,
+
+            labels: self.labels as f64,
+            hashtags: prose.hashtags as f64,
+
+            sentence_length_mean,
+            sentence_length_variance,
+            type_token_ratio: lexical.type_token_ratio,
+            mattr: lexical.mattr,
+            hapax_ratio: lexical.hapax_ratio,
+            avg_word_length: lexical.avg_word_length,
+            punctuation_diversity: self.punctuation.diversity(),
+
+            flesch_reading_ease: readability.flesch_reading_ease,
+            flesch_kincaid_grade: readability.flesch_kincaid_grade,
+            gunning_fog: readability.gunning_fog,
+            smog: readability.smog,
+
+            perplexity,
+            ai_log_likelihood_ratio,
+
+            invisible_chars: self.suspicious.invisible as f64,
+            unusual_whitespace: self.suspicious.unusual_whitespace as f64,
+            confusables: self.suspicious.confusables as f64,
+
+            headings: profile.headings as f64,
+            heading_depth: profile.heading_depth as f64,
+            emoji_headings: profile.emoji_headings as f64,
+            list_items: profile.list_items as f64,
+            list_depth: profile.list_depth as f64,
+            bold_lead_ins: profile.bold_lead_ins as f64,
+            tables: profile.tables as f64,
+            code_blocks: profile.code_blocks as f64,
+            link_density: profile.links as f64 / self.lexical.words().max(1) as f64,
+
+            inline_code: (self.inline_code + prose.code_tokens) as f64,
+            code_density,
+
+            urls: prose.urls as f64,
+            emails: prose.emails as f64,
+            mentions: prose.mentions as f64,
+            issue_refs: prose.issue_refs as f64,
+            numbers: prose.numbers as f64,
+
+            line_leading_emojis: emoji_profile.line_leading as f64,
+            inline_emojis: emoji_profile.inline as f64,
+            ai_emojis: emoji_profile.ai as f64,
+            human_emojis: emoji_profile.human as f64,
+            emojis_per_line: (emoji_profile.line_leading + emoji_profile.inline) as f64
+                / emoji_profile.lines.max(1) as f64,
+
+            misspelling_rate: self.misspellings as f64 / self.lexical.words().max(1) as f64,
+            elongations: self.elongations as f64,
+            missing_apostrophes: self.missing_apostrophes as f64,
+            texting_abbreviations: self.texting as f64,
+
+            template_sections: self.template_sections as f64,
+            boilerplate: phrases.count(PhraseMetric::Boilerplate) as f64,
+
+            repeated_trigrams: repeats.trigram_rate,
+            repeated_openers: repeats.opener_rate,
+            adjacent_repeats: repeats.adjacent as f64,
+            compression_ratio: self.compression.ratio(),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+/// Extensions a `name.ext` token has to end with to be a file, so `it.was` stays prose.
+const EXTENSIONS: &[&str] = &[
+    "asm", "bat", "c", "cfg", "cjs", "conf", "cpp", "cs", "css", "csv", "dart", "dll", "env", "ex",
+    "exe", "exs", "gd", "gif", "go", "gradle", "gz", "h", "hpp", "hs", "html", "ini", "ipynb",
+    "java", "jpeg", "jpg", "js", "json", "jsx", "kt", "lock", "log", "lua", "md", "mjs", "mp3",
+    "mp4", "nix", "nu", "php", "png", "ps1", "py", "rb", "rs", "scss", "sh", "so", "sql", "svelte",
+    "svg", "swift", "tar", "toml", "ts", "tscn", "tsx", "txt", "vue", "wasm", "wav", "xml", "yaml",
+    "yml", "zig", "zip",
+];
+
+/// `2024-12-05`, `12/05/2024`, `05.12.24`: three numbers with the same separator and a year at
+/// either end. The devlog phrases look for the month in these.
+fn is_date(token: &str) -> bool {
+    let Some(separator) = token.chars().find(|c| matches!(c, '-' | '/' | '.')) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = token.split(separator).collect();
+
+    let [first, middle, last] = parts[..] else {
+        return false;
+    };
+
+    let digits = |part: &str, lengths: &[usize]| {
+        lengths.contains(&part.len()) && part.chars().all(|c| c.is_ascii_digit())
+    };
+
+    digits(middle, &[1, 2])
+        && ((digits(first, &[4]) && digits(last, &[1, 2]))
+            || (digits(first, &[1, 2]) && digits(last, &[2, 4])))
+}
+
+/// Code-looking tokens in prose, so a programmer writing `--release`, `src/main.rs`, `v1.2.3`
+/// or `foo_bar->baz` doesn't get flagged for irregular dashes, arrows and hashtags.
+///
+/// Tokens without letters or digits are never code, a standalone `->` is how people type an
+/// arrow. Hyphenated words are only code with a digit in them, `foo-bar` is read as english.
+/// Neither counts as an irregular dash or arrow, only the unicode ones do.
+pub(crate) fn is_code_token(token: &str) -> bool {
+    let has_alphanumeric = token.chars().any(|c| c.is_alphanumeric());
+
+    if !has_alphanumeric || is_date(token) {
+        return false;
+    }
+
+    // cli flags, -v --release
+    if let Some(flag) = token.strip_prefix("--").or_else(|| token.strip_prefix('-'))
+        && flag.starts_with(|c: char| c.is_ascii_alphabetic())
+        && flag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '='))
+    {
+        return true;
+    }
+
+    // operators and identifiers
+    if ["->", "=>", "::", "\\", "_", "()", "{", "}", "==", "&&", "||"]
+        .iter()
+        .any(|pattern| token.contains(pattern))
+    {
+        return true;
+    }
+
+    // paths, but not and/or or 24/7
+    if token.contains('/')
+        && (token.starts_with(['/', '~', '.'])
+            || token.matches('/').count() >= 2
+            || token.contains('.'))
+    {
+        return true;
+    }
+
+    // versions, 1.2 v0.4.0 1.0.0-beta
+    let version = token.strip_prefix(['v', 'V']).unwrap_or(token);
+    if version.starts_with(|c: char| c.is_ascii_digit())
+        && version.contains('.')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+    {
+        return true;
+    }
+
+    // files, main.rs Node.js Cargo.toml, with an identifier before the extension
+    if let Some((stem, extension)) = token.rsplit_once('.')
+        && stem.chars().count() > 1
+        && stem
+            .split(['.', '-'])
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'))
+        && EXTENSIONS.contains(&extension)
+    {
+        return true;
+    }
+
+    // hyphenated words with digits are identifiers (x86-64, sha-256), without they're english
+    token.contains('-') && token.chars().any(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_tokens() {
+        for token in [
+            "-v",
+            "--release",
+            "--features=wasm",
+            "foo_bar->baz",
+            "snake_case",
+            "Vec::new",
+            "run()",
+            "a&&b",
+            "src/main.rs",
+            "~/.config",
+            "./build.sh",
+            "a/b/c",
+            "v0.4.0",
+            "1.2",
+            "1.0.0-beta",
+            "main.rs",
+            "Node.js",
+            "Cargo.toml",
+            "vite.config.ts",
+            "my-app.json",
+            "x86-64",
+            "sha-256",
+        ] {
+            assert!(is_code_token(token), "{token}");
+        }
+    }
+
+    #[test]
+    fn prose_tokens() {
+        for token in [
+            "->",
+            "=>",
+            "--",
+            "foo-bar",
+            "well-known",
+            "and/or",
+            "24/7",
+            "wait...what",
+            "it.was",
+            "e.g",
+            "i.e.",
+            "end.",
+            "2024-12-05",
+            "12/05/2024",
+            "05.12.2024",
+            "5/12/24",
+            "hello",
+        ] {
+            assert!(!is_code_token(token), "{token}");
+        }
+    }
+
+    #[test]
+    fn dates() {
+        for (token, date) in [
+            ("2024-12-05", true),
+            ("2024/1/5", true),
+            ("12/05/2024", true),
+            ("05.12.24", true),
+            ("1.2.3", false),
+            ("2024-12", false),
+            ("1.2.2024.1", false),
+            ("12-05/2024", false),
+            ("v2024.12.05", false),
+        ] {
+            assert_eq!(is_date(token), date, "{token}");
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use pulldown_cmark::{Event, Tag, TagEnd};
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Emoji set lookups ignore variation selectors, `⚠` and `⚠️` are the same emoji.
+pub(crate) fn emoji_key(emoji: &str) -> String {
+    emoji.replace('\u{FE0F}', "")
+}
+
+/// Where emojis sit, LLMs put them at the start of lines as bullets and section markers, humans
+/// react with them mid-sentence.
+#[derive(Default)]
+pub(crate) struct EmojiProfile {
+    pub line_leading: usize,
+    pub inline: usize,
+    pub ai: usize,
+    pub human: usize,
+    pub lines: usize,
+
+    line_start: bool,
+    in_code_block: bool,
+}
+
+impl EmojiProfile {
+    pub fn new() -> Self {
+        Self {
+            line_start: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn observe(&mut self, event: &Event, ai: &HashSet<String>, human: &HashSet<String>) {
+        match event {
+            Event::Start(
+                Tag::Paragraph
+                | Tag::Item
+                | Tag::Heading { .. }
+                | Tag::TableCell
+                | Tag::BlockQuote(_),
+            )
+            | Event::SoftBreak
+            | Event::HardBreak => self.line_start = true,
+            Event::Start(Tag::CodeBlock(_)) => self.in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => self.in_code_block = false,
+            Event::Text(text) if !self.in_code_block => {
+                for (i, line) in text.split('\n').enumerate() {
+                    self.line_start |= i > 0;
+                    self.observe_line(line, ai, human);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn observe_line(&mut self, text: &str, ai: &HashSet<String>, human: &HashSet<String>) {
+        for grapheme in text.graphemes(true) {
+            if grapheme.trim().is_empty() {
+                continue;
+            }
+
+            let line_start = std::mem::take(&mut self.line_start);
+            if line_start {
+                self.lines += 1;
+            }
+
+            if emojis::get(grapheme).is_none() {
+                continue;
+            }
+
+            if line_start {
+                self.line_leading += 1;
+            } else {
+                self.inline += 1;
+            }
+
+            let key = emoji_key(grapheme);
+            if ai.contains(&key) {
+                self.ai += 1;
+            } else if human.contains(&key) {
+                self.human += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use pulldown_cmark::{
+    CodeBlockKind, CowStr, Event, HeadingLevel, LinkType, Tag, TagEnd,
+};
+
+/// Most common named entities, anything not in here is left as is and isn't counted as an escaping
+/// artifact either.
+const ENTITIES: &[(&str, &str)] = &[
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", "\u{00A0}"),
+    ("ensp", "\u{2002}"),
+    ("emsp", "\u{2003}"),
+    ("thinsp", "\u{2009}"),
+    ("zwsp", "\u{200B}"),
+    ("zwnj", "\u{200C}"),
+    ("zwj", "\u{200D}"),
+    ("shy", "\u{00AD}"),
+    ("ndash", "–"),
+    ("mdash", "—"),
+    ("hellip", "…"),
+    ("lsquo", "‘"),
+    ("rsquo", "’"),
+    ("sbquo", "‚"),
+    ("ldquo", "“"),
+    ("rdquo", "”"),
+    ("bdquo", "„"),
+    ("laquo", "«"),
+    ("raquo", "»"),
+    ("bull", "•"),
+    ("middot", "·"),
+    ("larr", "←"),
+    ("rarr", "→"),
+    ("uarr", "↑"),
+    ("darr", "↓"),
+    ("harr", "↔"),
+    ("rArr", "⇒"),
+    ("lArr", "⇐"),
+    ("times", "×"),
+    ("divide", "÷"),
+    ("minus", "−"),
+    ("plusmn", "±"),
+    ("deg", "°"),
+    ("copy", "©"),
+    ("reg", "®"),
+    ("trade", "™"),
+    ("euro", "€"),
+    ("pound", "£"),
+    ("yen", "¥"),
+    ("cent", "¢"),
+    ("sect", "§"),
+    ("para", "¶"),
+    ("dagger", "†"),
+    ("hearts", "♥"),
+    ("check", "✓"),
+];
+
+/// Longest entity name or number that's decoded, the `;` is only looked for this far so a text
+/// full of `&` stays linear.
+const MAX_ENTITY_LEN: usize = 10;
+
+/// Length of the entity at the start of `text` (which starts with `&`) and what it decodes to.
+fn entity(text: &str) -> Option<(usize, String)> {
+    let end = text
+        .bytes()
+        .skip(1)
+        .take(MAX_ENTITY_LEN + 1)
+        .position(|b| b == b';')?
+        + 1;
+    let name = &text[1..end];
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let decoded = if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+
+        char::from_u32(code)?.to_string()
+    } else {
+        ENTITIES
+            .iter()
+            .find(|(entity, _)| *entity == name)?
+            .1
+            .to_string()
+    };
+
+    Some((end + 1, decoded))
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        match entity(rest) {
+            Some((len, value)) => {
+                decoded.push_str(&value);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Entities still present after decoding once, `&amp;amp;` or `&amp;#39;` from text that was
+/// escaped twice on its way here.
+pub(crate) fn count_entities(text: &str) -> usize {
+    text.match_indices('&')
+        .filter(|&(i, _)| entity(&text[i..]).is_some())
+        .count()
+}
+
+pub(crate) struct Html {
+    pub events: Vec<Event<'static>>,
+    pub escape_artifacts: usize,
+}
+
+fn heading_level(name: &str) -> Option<HeadingLevel> {
+    Some(match name {
+        "h1" => HeadingLevel::H1,
+        "h2" => HeadingLevel::H2,
+        "h3" => HeadingLevel::H3,
+        "h4" => HeadingLevel::H4,
+        "h5" => HeadingLevel::H5,
+        "h6" => HeadingLevel::H6,
+        _ => return None,
+    })
+}
+
+fn start(name: &str) -> Option<Event<'static>> {
+    let empty = || CowStr::Borrowed("");
+
+    let tag = match name {
+        "p" => Tag::Paragraph,
+        "ul" => Tag::List(None),
+        "ol" => Tag::List(Some(1)),
+        "li" => Tag::Item,
+        "strong" | "b" => Tag::Strong,
+        "em" | "i" => Tag::Emphasis,
+        "s" | "del" | "strike" => Tag::Strikethrough,
+        "sub" => Tag::Subscript,
+        "sup" => Tag::Superscript,
+        "blockquote" => Tag::BlockQuote(None),
+        "pre" => Tag::CodeBlock(CodeBlockKind::Indented),
+        "table" => Tag::Table(vec![]),
+        "thead" => Tag::TableHead,
+        "tr" => Tag::TableRow,
+        "td" | "th" => Tag::TableCell,
+        "a" => Tag::Link {
+            link_type: LinkType::Inline,
+            dest_url: empty(),
+            title: empty(),
+            id: empty(),
+        },
+        "img" => Tag::Image {
+            link_type: LinkType::Inline,
+            dest_url: empty(),
+            title: empty(),
+            id: empty(),
+        },
+        "hr" => return Some(Event::Rule),
+        "br" => return Some(Event::HardBreak),
+        name => Tag::Heading {
+            level: heading_level(name)?,
+            id: None,
+            classes: vec![],
+            attrs: vec![],
+        },
+    };
+
+    Some(Event::Start(tag))
+}
+
+fn end(name: &str) -> Option<Event<'static>> {
+    let tag = match name {
+        "p" => TagEnd::Paragraph,
+        "ul" => TagEnd::List(false),
+        "ol" => TagEnd::List(true),
+        "li" => TagEnd::Item,
+        "strong" | "b" => TagEnd::Strong,
+        "em" | "i" => TagEnd::Emphasis,
+        "s" | "del" | "strike" => TagEnd::Strikethrough,
+        "sub" => TagEnd::Subscript,
+        "sup" => TagEnd::Superscript,
+        "blockquote" => TagEnd::BlockQuote(None),
+        "pre" => TagEnd::CodeBlock,
+        "table" => TagEnd::Table,
+        "thead" => TagEnd::TableHead,
+        "tr" => TagEnd::TableRow,
+        "td" | "th" => TagEnd::TableCell,
+        "a" => TagEnd::Link,
+        name => TagEnd::Heading(heading_level(name)?),
+    };
+
+    Some(Event::End(tag))
+}
+
+/// Turns (sanitized) HTML into the same events pulldown-cmark produces for the equivalent
+/// Markdown, so both go through the exact same metrics. Not a real HTML parser, attributes are
+/// ignored and unknown tags are dropped.
+pub(crate) fn parse(html: &str) -> Html {
+    let mut events = Vec::new();
+    let mut escape_artifacts = 0;
+    let mut rest = html;
+    let mut in_pre = false;
+    let mut inline_code: Option<String> = None;
+
+    let mut push_text = |events: &mut Vec<Event<'static>>,
+                         inline_code: &mut Option<String>,
+                         raw: &str,
+                         in_pre: bool| {
+        let decoded = decode_entities(raw);
+        escape_artifacts += count_entities(&decoded);
+
+        let text = if in_pre {
+            decoded
+        } else {
+            // html collapses whitespace
+            let collapsed = decoded
+                .split_ascii_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut text = collapsed;
+
+            if !text.is_empty() && raw.starts_with(|c: char| c.is_ascii_whitespace()) {
+                text.insert(0, ' ');
+            }
+            if !text.is_empty() && raw.ends_with(|c: char| c.is_ascii_whitespace()) {
+                text.push(' ');
+            }
+
+            text
+        };
+
+        if text.is_empty() {
+            return;
+        }
+
+        match inline_code {
+            Some(code) => code.push_str(&text),
+            None => events.push(Event::Text(text.into())),
+        }
+    };
+
+    while let Some(i) = rest.find('<') {
+        push_text(&mut events, &mut inline_code, &rest[..i], in_pre);
+        rest = &rest[i..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(close) = rest.find('>') else {
+            // a lone <, it's text
+            push_text(&mut events, &mut inline_code, rest, in_pre);
+            rest = "";
+            break;
+        };
+
+        let tag = &rest[1..close];
+        rest = &rest[close + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_ascii_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        if !closing && matches!(name.as_str(), "script" | "style") {
+            let end = format!("</{name}");
+            rest = rest
+                .to_ascii_lowercase()
+                .find(&end)
+                .and_then(|i| rest[i..].find('>').map(|j| &rest[i + j + 1..]))
+                .unwrap_or("");
+            continue;
+        }
+
+        match (name.as_str(), closing) {
+            ("code", false) if !in_pre => inline_code = Some(String::new()),
+            ("code", true) if !in_pre => {
+                if let Some(code) = inline_code.take() {
+                    events.push(Event::Code(code.into()));
+                }
+            }
+            ("img", false) => {
+                events.extend(start("img"));
+                events.push(Event::End(TagEnd::Image));
+            }
+            (name, false) => {
+                in_pre |= name == "pre";
+                events.extend(start(name));
+            }
+            (name, true) => {
+                in_pre &= name != "pre";
+                events.extend(end(name));
+            }
+        }
+    }
+
+    push_text(&mut events, &mut inline_code, rest, in_pre);
+
+    // an unclosed <code> ends with the text
+    if let Some(code) = inline_code {
+        events.push(Event::Code(code.into()));
+    }
+
+    Html {
+        events,
+        escape_artifacts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entities() {
+        for (text, expected) in [
+            ("&amp; more", Some((5, "&"))),
+            ("&#8203;", Some((7, "\u{200B}"))),
+            ("&#x430;", Some((7, "\u{0430}"))),
+            ("&amp", None),
+            ("&;", None),
+            ("&unknown;", None),
+            ("& then; later", None),
+            ("&aaaaaaaaaaa;", None),
+            ("&é;", None),
+        ] {
+            let entity = entity(text);
+            let entity = entity.as_ref().map(|(len, value)| (*len, value.as_str()));
+            assert_eq!(entity, expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn unclosed_code_ends_with_the_text() {
+        let html = parse("<p>run <code>cargo build</p>");
+
+        assert!(
+            html.events
+                .iter()
+                .any(|event| matches!(event, Event::Code(code) if code.as_ref() == "cargo build"))
+        );
+    }
+}
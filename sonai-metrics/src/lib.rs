@@ -1,22 +1,38 @@
 #![deny(clippy::all)]
 
-use aho_corasick::AhoCorasick;
 use linfa_clustering::KMeans;
 use linfa_nn::distance::Distance;
 use linfa_nn::distance::L2Dist;
 use ndarray::{Array1, Array2, ArrayView1, Axis};
-use pulldown_cmark::Event;
-use pulldown_cmark::Parser;
-use pulldown_cmark::Tag;
-use pulldown_cmark::TagEnd;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fmt;
-use unicode_segmentation::UnicodeSegmentation;
+use std::io::{self, BufRead};
+
+mod accumulator;
+mod code;
+mod emoji;
+mod html;
+mod markdown;
+mod ngram;
+mod normalize;
+mod phrases;
+mod readability;
+mod repetition;
+mod spelling;
+mod stylometry;
+mod template;
+mod tokens;
+mod version;
+
+pub use accumulator::MetricAccumulator;
+pub use ngram::{LanguageModels, NgramModel};
+pub use normalize::normalize;
 
 pub type DistanceFunction = L2Dist;
 pub const DIST_FN: DistanceFunction = L2Dist;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextMetrics {
     // higher = more AI-like
     pub emoji_rate: f64,    // Emoji * 2 / sentences
@@ -37,6 +53,75 @@ pub struct TextMetrics {
 
     pub labels: f64,
     pub hashtags: f64,
+
+    // AI text is uniform, humans are bursty
+    pub sentence_length_mean: f64,     // Words per sentence
+    pub sentence_length_variance: f64, // Burstiness
+    pub type_token_ratio: f64,         // Unique words / words
+    pub mattr: f64,                    // Moving-average TTR, length independent
+    pub hapax_ratio: f64,              // Words used exactly once / words
+    pub avg_word_length: f64,          // Characters per word
+    pub punctuation_diversity: f64,    // Entropy of punctuation marks
+
+    // LLMs write at a very consistent grade level
+    pub flesch_reading_ease: f64,
+    pub flesch_kincaid_grade: f64,
+    pub gunning_fog: f64,
+    pub smog: f64,
+
+    // 0 unless the factory has language models
+    pub perplexity: f64,              // Under the human model
+    pub ai_log_likelihood_ratio: f64, // log2 P(ai) - log2 P(human) per char
+
+    // paste-through from chat UIs
+    pub invisible_chars: f64,    // Zero-width spaces, soft hyphens, BOMs
+    pub unusual_whitespace: f64, // NBSP, narrow NBSP, em spaces
+    pub confusables: f64,        // Words mixing latin with cyrillic/greek lookalikes
+
+    // markdown shape
+    pub headings: f64,
+    pub heading_depth: f64, // Deepest heading level used
+    pub emoji_headings: f64, // ## 🚀 Features
+    pub list_items: f64,
+    pub list_depth: f64,    // Deepest list nesting
+    pub bold_lead_ins: f64, // - **Fast:** blah
+    pub tables: f64,
+    pub code_blocks: f64,
+    pub link_density: f64, // Links / words
+
+    // programmers write code in their devlogs, that isn't AI
+    pub inline_code: f64,  // `code` spans + code-looking tokens in prose
+    pub code_density: f64, // Code chars / all chars
+
+    // each token is counted once, #42 is an issue not a hashtag
+    pub urls: f64,
+    pub emails: f64,
+    pub mentions: f64,   // @someone
+    pub issue_refs: f64, // #42, but not devlog #3
+    pub numbers: f64,
+
+    // emoji profile
+    pub line_leading_emojis: f64, // 🚀 Fast startup
+    pub inline_emojis: f64,       // that took forever 😭
+    pub ai_emojis: f64,           // From the AI-typical set
+    pub human_emojis: f64,        // From the human-typical set
+    pub emojis_per_line: f64,
+
+    // typos are one of the strongest human signals
+    pub misspelling_rate: f64,      // Misspelled words / words
+    pub elongations: f64,           // sooo, yesss
+    pub missing_apostrophes: f64,   // dont, im
+    pub texting_abbreviations: f64, // idk, tbh, u
+
+    // scaffolds and default texts
+    pub template_sections: f64, // ## Features, Tech Stack:, Installation
+    pub boilerplate: f64,       // Known default texts, "This is my first project on Flavortown."
+
+    // formulaic AI text vs low-effort spam
+    pub repeated_trigrams: f64, // Word trigram occurrences that repeat / trigrams
+    pub repeated_openers: f64,  // Sentences whose first two words repeat / sentences
+    pub adjacent_repeats: f64,  // vote vote vote
+    pub compression_ratio: f64, // LZ78 estimate, lower = more repetitive
 }
 
 impl fmt::Display for TextMetrics {
@@ -59,6 +144,53 @@ impl fmt::Display for TextMetrics {
             ("labels", self.labels),
             ("hashtags", self.hashtags),
             ("backstory", self.backstory_count),
+            ("sent_len", self.sentence_length_mean),
+            ("sent_var", self.sentence_length_variance),
+            ("ttr", self.type_token_ratio),
+            ("mattr", self.mattr),
+            ("hapax", self.hapax_ratio),
+            ("word_len", self.avg_word_length),
+            ("punct_div", self.punctuation_diversity),
+            ("flesch", self.flesch_reading_ease),
+            ("fk_grade", self.flesch_kincaid_grade),
+            ("fog", self.gunning_fog),
+            ("smog", self.smog),
+            ("ppl", self.perplexity),
+            ("ai_llr", self.ai_log_likelihood_ratio),
+            ("invisible", self.invisible_chars),
+            ("odd_space", self.unusual_whitespace),
+            ("confus", self.confusables),
+            ("headings", self.headings),
+            ("head_dep", self.heading_depth),
+            ("emoji_hd", self.emoji_headings),
+            ("items", self.list_items),
+            ("list_dep", self.list_depth),
+            ("bold_lead", self.bold_lead_ins),
+            ("tables", self.tables),
+            ("code", self.code_blocks),
+            ("links", self.link_density),
+            ("inl_code", self.inline_code),
+            ("code_dens", self.code_density),
+            ("urls", self.urls),
+            ("emails", self.emails),
+            ("mentions", self.mentions),
+            ("issues", self.issue_refs),
+            ("numbers", self.numbers),
+            ("emo_lead", self.line_leading_emojis),
+            ("emo_inl", self.inline_emojis),
+            ("emo_ai", self.ai_emojis),
+            ("emo_hum", self.human_emojis),
+            ("emo_line", self.emojis_per_line),
+            ("misspell", self.misspelling_rate),
+            ("elongate", self.elongations),
+            ("no_apos", self.missing_apostrophes),
+            ("texting", self.texting_abbreviations),
+            ("template", self.template_sections),
+            ("boiler", self.boilerplate),
+            ("rep_3gram", self.repeated_trigrams),
+            ("rep_open", self.repeated_openers),
+            ("adj_rep", self.adjacent_repeats),
+            ("compress", self.compression_ratio),
         ];
 
         let mut cell = 0u8;
@@ -102,40 +234,75 @@ impl fmt::Display for TextMetrics {
     }
 }
 
+/// What kind of text [`TextMetricFactory::calculate`] is given.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// No markup at all, `**this**` is just text.
+    Plain,
+    #[default]
+    Markdown,
+    /// Tags are stripped and entities decoded, the structure is measured the same way as the
+    /// Markdown equivalent.
+    Html,
+}
+
 #[derive(Debug)]
 pub struct TextMetricFactory {
-    buzzword_ahocorasick: AhoCorasick,
-    negative_buzzword_ahocorasick: AhoCorasick,
-    not_just_ahocorasick: AhoCorasick,
-    devlog_ahocorasick: AhoCorasick,
-    irr_ell_ahocorasick: AhoCorasick,
-    backstory_ahocorasick: AhoCorasick,
-    negative_backstory_ahocorasick: AhoCorasick,
-    incorrect_perspective_ahocorasick: AhoCorasick,
-    broken_english_ahocorasick: AhoCorasick,
-    mr_fancy_pants_ahocorasick: AhoCorasick,
+    phrases: phrases::Phrases,
+    spelling: spelling::Spelling,
+    language_models: LanguageModels,
+    input_format: InputFormat,
+    ai_emojis: HashSet<String>,
+    human_emojis: HashSet<String>,
+}
+
+fn emoji_set<I, S>(emojis: I) -> HashSet<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    emojis
+        .into_iter()
+        .map(|emoji| emoji::emoji_key(emoji.as_ref()))
+        .collect()
 }
 
 impl TextMetricFactory {
     pub fn new() -> Result<Self, aho_corasick::BuildError> {
         Ok(Self {
-            buzzword_ahocorasick: AhoCorasick::new(include!("lists/buzzword.rs"))?,
-            negative_buzzword_ahocorasick: AhoCorasick::new(include!(
-                "lists/negative_buzzword.rs"
-            ))?,
-            broken_english_ahocorasick: AhoCorasick::new(include!("lists/broken_english.rs"))?,
-            mr_fancy_pants_ahocorasick: AhoCorasick::new(["(e.g.", "(formerly", "role- "])?,
-            not_just_ahocorasick: AhoCorasick::new(include!("lists/not_just.rs"))?,
-            devlog_ahocorasick: AhoCorasick::new(include!("lists/devlog.rs"))?,
-            irr_ell_ahocorasick: AhoCorasick::new(["…", "..."])?,
-            incorrect_perspective_ahocorasick: AhoCorasick::new(include!(
-                "lists/incorrect_perspective.rs"
-            ))?,
-            backstory_ahocorasick: AhoCorasick::new(include!("lists/backstory.rs"))?,
-            negative_backstory_ahocorasick: AhoCorasick::new(include!("lists/negative_backstory.rs"))?,
+            phrases: phrases::Phrases::new()?,
+            spelling: spelling::Spelling::new(),
+            language_models: LanguageModels::default(),
+            input_format: InputFormat::default(),
+            ai_emojis: emoji_set(include!("lists/ai_emoji.rs")),
+            human_emojis: emoji_set(include!("lists/human_emoji.rs")),
         })
     }
 
+    /// Replaces the AI-typical and human-typical emoji sets, human-typical emojis don't count
+    /// towards `emoji_rate`.
+    pub fn with_emoji_sets<A, H, S>(mut self, ai: A, human: H) -> Self
+    where
+        A: IntoIterator<Item = S>,
+        H: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.ai_emojis = emoji_set(ai);
+        self.human_emojis = emoji_set(human);
+        self
+    }
+
+    pub fn with_input_format(mut self, input_format: InputFormat) -> Self {
+        self.input_format = input_format;
+        self
+    }
+
+    /// Enables the perplexity and log-likelihood ratio metrics.
+    pub fn with_language_models(mut self, language_models: LanguageModels) -> Self {
+        self.language_models = language_models;
+        self
+    }
+
     pub fn calculate_iter<I, S>(&self, texts: I) -> impl Iterator<Item = TextMetrics>
     where
         I: IntoIterator<Item = S>,
@@ -145,174 +312,34 @@ impl TextMetricFactory {
     }
 
     pub fn calculate(&self, text: &str) -> TextMetrics {
-        // existing markdown vs non-markdown
-
-        // slow but fine, only one.
-        let html_escapes = text.matches("&amp;").count();
-
-        let mut markdown = text.matches(['•', '●']).count(); // Lists are OK, this shit is not
-        let mut cleaned_text = String::new();
-        let mut in_code_block = false;
-
-        for event in Parser::new(text) {
-            if matches!(
-                event,
-                Event::Rule
-                    | Event::Start(
-                        Tag::BlockQuote(_)
-                            | Tag::Emphasis
-                            | Tag::Subscript
-                            | Tag::Superscript
-                            | Tag::Strong
-                            | Tag::Strikethrough
-                            | Tag::Heading { .. }
-                            | Tag::Link { .. }
-                            | Tag::Image { .. }
-                    )
-            ) {
-                markdown += 1;
-            }
-
-            match event {
-                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
-                Event::End(TagEnd::CodeBlock) => in_code_block = false,
-                Event::Text(t) if !in_code_block => cleaned_text.push_str(&t),
-                Event::SoftBreak | Event::HardBreak if !in_code_block => cleaned_text.push(' '),
-                _ => {}
-            }
-        }
-
-        let text = cleaned_text.trim().replace("\n\n", "\n");
-
-        let mut noncap_sentences = 0;
-
-        let sentence_count = text
-            .split(['.', '!', '?', '\n'])
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .inspect(|sentence| {
-                if let Some(first_char) = sentence.chars().next()
-                    && first_char.is_ascii() && !first_char.is_uppercase()
-                {
-                    noncap_sentences += 1;
-                }
-            })
-            .count()
-            .max(1);
-
-        let text = text.to_ascii_lowercase();
-
-        let mut labels = 0usize;
-
-        for line in text.lines() {
-            if let Some((label, after)) = line.split_once(':') {
-                if !after.trim().is_empty() {
-                    continue;
-                }
-
-                let label = label.trim();
-
-                if !label.is_empty()
-                    && label
-                        .chars()
-                        .all(|c| c.is_alphabetic() || c.is_whitespace()) && !matches!(label, "https" | "http")
-                {
-                    labels += 1;
-                }
-            }
-        }
-
-        let text = text.replace("\n", " ").replace("  ", " ");
-
-        let words = text.split_whitespace().filter(|w| !w.trim().is_empty());
-
-        let mut hashtags = 0usize;
-        for word in words {
-            if word.starts_with('#') && word.len() > 1 {
-                hashtags += 1;
-            }
-        }
-
-        let mut emoji_count = 0;
-        let mut irr_dash = 0;
-        let mut irr_quote = 0;
-        let mut irr_arr = 0;
-
-        for grapheme in text.graphemes(true) {
-            if emojis::get(grapheme).is_some() && !matches!(grapheme, "😭" | "😉" | "🫣") {
-                emoji_count += 1;
-                continue;
-            }
-
-            let mut iter = grapheme.chars().peekable();
-
-            while let Some(c) = iter.next() {
-                match c {
-                    '–' | '—' | '‒' | '―' | '⸻' | '⸺' | '−' | '﹘' | '－' | '‑' | '‐' | '᠆'
-                    | '־' | '֊' => irr_dash += 1,
-                    '→' | '↑' | '↓' | '↔' | '↕' | '⇒' | '⇐' | '⇑' | '⇓' | '➔' | '➜' => {
-                        irr_arr += 1
-                    }
-                    '“' | '”' | '‘' | '’' => irr_quote += 1,
-                    '-' => {
-                        if iter.peek().is_some_and(|x| !x.is_whitespace()) {
-                            irr_dash += 1;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        }
-
-        // tradeoff is fine for a match list this small
-        let irr_ell = self.irr_ell_ahocorasick.find_iter(&text).count();
-
-        let sc = sentence_count as f64;
-
-        let dev_log = self.devlog_ahocorasick.find_iter(&text).count();
-
-        let buzzwords = self.buzzword_ahocorasick.find_iter(&text).count() as f64
-            - self.negative_buzzword_ahocorasick.find_iter(&text).count() as f64;
-
-        let not_just = self.not_just_ahocorasick.find_iter(&text).count();
-
-        let backstory = self.backstory_ahocorasick.find_iter(&text).count() as f64 - self.negative_backstory_ahocorasick.find_iter(&text).count() as f64;
-        let incper = self
-            .incorrect_perspective_ahocorasick
-            .find_iter(&text)
-            .count();
-
-        // fancy can also be interpreted as proper english. trailing commas are NOT proper english
-        let informality = (if text.ends_with(",") { 1. } else { 0. })
-            + self.broken_english_ahocorasick.find_iter(&text).count() as f64
-            + 1.5 * noncap_sentences as f64
-            - self.mr_fancy_pants_ahocorasick.find_iter(&text).count() as f64;
-
-        TextMetrics {
-            emoji_rate: (emoji_count as f64) / sc,
-            buzzword_rate: buzzwords / sc,
-            backstory_count: backstory,
-            incorrect_perspective: (incper as f64) /sc,
-            human_informality: informality / sc,
-
-            devlog_count: dev_log as f64,
-            html_escape_count: html_escapes as f64,
-            not_just_count: not_just as f64,
+        let mut accumulator = self.accumulator();
+        accumulator.push(text);
+        accumulator.finish()
+    }
 
-            irregular_quotations: (irr_quote as f64) / sc,
-            irregular_dashes: irr_dash as f64,
-            irregular_arrows: irr_arr as f64,
-            irregular_ellipsis: irr_ell as f64,
-            irregular_markdown: markdown as f64,
+    /// Same as [`TextMetricFactory::calculate`] without reading the whole text into memory first.
+    pub fn calculate_reader<R: BufRead>(&self, reader: R) -> io::Result<TextMetrics> {
+        let mut accumulator = self.accumulator();
+        accumulator.read_from(reader)?;
+        Ok(accumulator.finish())
+    }
 
-            labels: labels as f64,
-            hashtags: hashtags as f64,
-        }
+    pub fn accumulator(&self) -> MetricAccumulator<'_> {
+        MetricAccumulator::new(self)
     }
 }
 
+/// Number of columns produced by [`features_from_metrics`].
+pub const N_FEATURES: usize = 62;
+
+/// Columns that need [`TextMetricFactory::with_language_models`], the language models are trained
+/// from a first clustering that has to leave these out.
+pub const LANGUAGE_MODEL_FEATURES: [usize; 2] = [26, 27];
+
+/// Columns are append-only, a model trained on the first `n` columns keeps working when new
+/// features are added after it.
 pub fn features_from_metrics(data: &[&TextMetrics]) -> Array2<f64> {
-    let n_features = 15;
+    let n_features = N_FEATURES;
     let n_samples = data.len();
 
     let mut array = Array2::<f64>::zeros((n_samples, n_features));
@@ -333,6 +360,53 @@ pub fn features_from_metrics(data: &[&TextMetrics]) -> Array2<f64> {
         array[[i, 12]] = sample.incorrect_perspective;
         array[[i, 13]] = sample.backstory_count;
         array[[i, 14]] = sample.irregular_arrows;
+        array[[i, 15]] = sample.sentence_length_mean;
+        array[[i, 16]] = sample.sentence_length_variance;
+        array[[i, 17]] = sample.type_token_ratio;
+        array[[i, 18]] = sample.mattr;
+        array[[i, 19]] = sample.hapax_ratio;
+        array[[i, 20]] = sample.avg_word_length;
+        array[[i, 21]] = sample.punctuation_diversity;
+        array[[i, 22]] = sample.flesch_reading_ease;
+        array[[i, 23]] = sample.flesch_kincaid_grade;
+        array[[i, 24]] = sample.gunning_fog;
+        array[[i, 25]] = sample.smog;
+        array[[i, 26]] = sample.perplexity;
+        array[[i, 27]] = sample.ai_log_likelihood_ratio;
+        array[[i, 28]] = sample.invisible_chars;
+        array[[i, 29]] = sample.unusual_whitespace;
+        array[[i, 30]] = sample.confusables;
+        array[[i, 31]] = sample.headings;
+        array[[i, 32]] = sample.heading_depth;
+        array[[i, 33]] = sample.emoji_headings;
+        array[[i, 34]] = sample.list_items;
+        array[[i, 35]] = sample.list_depth;
+        array[[i, 36]] = sample.bold_lead_ins;
+        array[[i, 37]] = sample.tables;
+        array[[i, 38]] = sample.code_blocks;
+        array[[i, 39]] = sample.link_density;
+        array[[i, 40]] = sample.inline_code;
+        array[[i, 41]] = sample.code_density;
+        array[[i, 42]] = sample.urls;
+        array[[i, 43]] = sample.emails;
+        array[[i, 44]] = sample.mentions;
+        array[[i, 45]] = sample.issue_refs;
+        array[[i, 46]] = sample.numbers;
+        array[[i, 47]] = sample.line_leading_emojis;
+        array[[i, 48]] = sample.inline_emojis;
+        array[[i, 49]] = sample.ai_emojis;
+        array[[i, 50]] = sample.human_emojis;
+        array[[i, 51]] = sample.emojis_per_line;
+        array[[i, 52]] = sample.misspelling_rate;
+        array[[i, 53]] = sample.elongations;
+        array[[i, 54]] = sample.missing_apostrophes;
+        array[[i, 55]] = sample.texting_abbreviations;
+        array[[i, 56]] = sample.template_sections;
+        array[[i, 57]] = sample.boilerplate;
+        array[[i, 58]] = sample.repeated_trigrams;
+        array[[i, 59]] = sample.repeated_openers;
+        array[[i, 60]] = sample.adjacent_repeats;
+        array[[i, 61]] = sample.compression_ratio;
     }
 
     array
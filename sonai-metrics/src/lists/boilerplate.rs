@@ -0,0 +1,24 @@
+[
+    // flavortown defaults
+    "this is my first project on flavortown.",
+    "im excited to share my progress!",
+    "i'm excited to share my progress!",
+    "i’m excited to share my progress!",
+    "i'm working on my first project! this is so exciting. i can't wait to share more updates as i build.",
+    "i’m working on my first project! this is so exciting. i can’t wait to share more updates as i build.",
+    // scaffolds
+    "this project was bootstrapped with",
+    "this template provides a minimal setup",
+    "currently, two official plugins are available",
+    "to get a local copy up and running",
+    "contributions are what make the open source community",
+    "pull requests are welcome",
+    "contributions are welcome",
+    "feel free to open an issue",
+    "distributed under the mit license",
+    "this project is licensed under the",
+    "see the license file for details",
+    "don't forget to give the project a star",
+    "give it a star",
+    "lorem ipsum",
+]
@@ -0,0 +1,5 @@
+[
+    "😭", // not counted towards emoji_rate
+    "😉",
+    "🫣",
+]
@@ -0,0 +1,45 @@
+// only the ones that aren't words on their own, so no its, were, well, ill, hell, lets, wed
+[
+    "dont",
+    "cant",
+    "wont",
+    "im",
+    "ive",
+    "isnt",
+    "arent",
+    "wasnt",
+    "werent",
+    "doesnt",
+    "didnt",
+    "hasnt",
+    "havent",
+    "hadnt",
+    "shouldnt",
+    "wouldnt",
+    "couldnt",
+    "mustnt",
+    "youre",
+    "youve",
+    "youll",
+    "youd",
+    "theyre",
+    "theyve",
+    "theyll",
+    "theyd",
+    "weve",
+    "thats",
+    "whats",
+    "wheres",
+    "whos",
+    "theres",
+    "heres",
+    "hes",
+    "shes",
+    "itll",
+    "thatll",
+    "aint",
+    "idve",
+    "wouldve",
+    "couldve",
+    "shouldve",
+]
@@ -0,0 +1,237 @@
+// common misspellings, flagged whatever their length, words with a missing apostrophe are in
+// missing_apostrophe.rs
+[
+    "accomodate",
+    "accomodation",
+    "accross",
+    "acheive",
+    "acheived",
+    "acheivement",
+    "acommodate",
+    "adn",
+    "adress",
+    "adressed",
+    "agressive",
+    "alittle",
+    "allready",
+    "alot",
+    "alwasy",
+    "amature",
+    "apparant",
+    "apparantly",
+    "aquire",
+    "aquired",
+    "arguement",
+    "assasinate",
+    "aswell",
+    "athiest",
+    "atleast",
+    "awesom",
+    "awsome",
+    "basicly",
+    "beacuse",
+    "beatiful",
+    "beautyful",
+    "becasue",
+    "becuase",
+    "beggining",
+    "begining",
+    "beleive",
+    "beleived",
+    "belive",
+    "bizzare",
+    "buisness",
+    "calender",
+    "carribean",
+    "catagory",
+    "cemetary",
+    "changable",
+    "cheif",
+    "collegue",
+    "comming",
+    "commited",
+    "commitee",
+    "completly",
+    "concious",
+    "consciencious",
+    "curiousity",
+    "decieve",
+    "definately",
+    "definatly",
+    "definetly",
+    "defintely",
+    "desparate",
+    "develope",
+    "developement",
+    "developped",
+    "diffrent",
+    "dilema",
+    "disapear",
+    "disapoint",
+    "dissapear",
+    "dissapoint",
+    "dosent",
+    "eachother",
+    "embarass",
+    "enviorment",
+    "enviroment",
+    "everytime",
+    "exagerate",
+    "excercise",
+    "existance",
+    "experiance",
+    "explaination",
+    "familar",
+    "fasinating",
+    "finaly",
+    "finnaly",
+    "firey",
+    "foriegn",
+    "fourty",
+    "foward",
+    "freind",
+    "frist",
+    "fullfil",
+    "futher",
+    "garantee",
+    "gaurd",
+    "goverment",
+    "gramatical",
+    "greatful",
+    "guage",
+    "happend",
+    "harrass",
+    "heighth",
+    "heirarchy",
+    "hte",
+    "humerous",
+    "hygene",
+    "ignorence",
+    "imediately",
+    "immediatly",
+    "incase",
+    "independant",
+    "indispensible",
+    "infact",
+    "inspite",
+    "inteligence",
+    "interupt",
+    "intresting",
+    "irrelevent",
+    "jsut",
+    "knowlege",
+    "lenght",
+    "liason",
+    "libary",
+    "lisence",
+    "maintainance",
+    "maintenence",
+    "managment",
+    "millenium",
+    "miniture",
+    "mischevious",
+    "mispell",
+    "mispelled",
+    "nad",
+    "neccessary",
+    "necessery",
+    "neice",
+    "nieghbor",
+    "noone",
+    "noticable",
+    "occassion",
+    "occassionally",
+    "occured",
+    "occurence",
+    "occuring",
+    "ommision",
+    "oppurtunity",
+    "orginal",
+    "paralel",
+    "parliment",
+    "peice",
+    "perseverence",
+    "persistant",
+    "personel",
+    "posession",
+    "potatos",
+    "preceeding",
+    "privelege",
+    "probaly",
+    "probbaly",
+    "proffesional",
+    "programing",
+    "promiss",
+    "pronounciation",
+    "publically",
+    "quarentine",
+    "realy",
+    "reccomend",
+    "reciept",
+    "recieve",
+    "recieved",
+    "recomend",
+    "recommed",
+    "referance",
+    "refered",
+    "relevent",
+    "religous",
+    "rember",
+    "remeber",
+    "repitition",
+    "resistence",
+    "resturant",
+    "rythm",
+    "sargent",
+    "scedule",
+    "secratary",
+    "seige",
+    "sentance",
+    "seperate",
+    "seperately",
+    "sieze",
+    "similiar",
+    "sincerly",
+    "speach",
+    "succesful",
+    "successfull",
+    "supose",
+    "suprise",
+    "suprised",
+    "surley",
+    "taht",
+    "tatoo",
+    "teh",
+    "tendancy",
+    "therefor",
+    "thier",
+    "threshhold",
+    "thsi",
+    "tihs",
+    "tommorow",
+    "tomorow",
+    "tounge",
+    "truely",
+    "tyrany",
+    "unforseen",
+    "unfortunatly",
+    "untill",
+    "upto",
+    "usefull",
+    "vaccum",
+    "vegtable",
+    "vehical",
+    "visable",
+    "waht",
+    "wether",
+    "whereever",
+    "whit",
+    "wich",
+    "wierd",
+    "wierdly",
+    "wiht",
+    "withold",
+    "writen",
+    "writting",
+    "wrtie",
+]
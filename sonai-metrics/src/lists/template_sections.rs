@@ -0,0 +1,43 @@
+[
+    "about",
+    "about the project",
+    "acknowledgements",
+    "acknowledgments",
+    "built with",
+    "challenges",
+    "configuration",
+    "contact",
+    "contributing",
+    "credits",
+    "demo",
+    "deployment",
+    "description",
+    "faq",
+    "features",
+    "future improvements",
+    "future plans",
+    "getting started",
+    "how it works",
+    "how to run",
+    "how to use",
+    "installation",
+    "key features",
+    "license",
+    "next steps",
+    "overview",
+    "prerequisites",
+    "project structure",
+    "requirements",
+    "roadmap",
+    "screenshots",
+    "setup",
+    "table of contents",
+    "tech stack",
+    "technologies used",
+    "testing",
+    "usage",
+    "what i learned",
+    "what i built",
+    "what's next",
+    "whats next",
+]
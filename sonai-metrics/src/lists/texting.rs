@@ -0,0 +1,67 @@
+[
+    "lol",
+    "lmao",
+    "lmfao",
+    "rofl",
+    "idk",
+    "idc",
+    "tbh",
+    "btw",
+    "imo",
+    "imho",
+    "brb",
+    "gtg",
+    "ttyl",
+    "omg",
+    "omfg",
+    "wtf",
+    "smh",
+    "ngl",
+    "fr",
+    "rn",
+    "u",
+    "ur",
+    "pls",
+    "plz",
+    "thx",
+    "ty",
+    "tysm",
+    "bc",
+    "cuz",
+    "cos",
+    "tho",
+    "ppl",
+    "msg",
+    "abt",
+    "jk",
+    "nvm",
+    "irl",
+    "afaik",
+    "iirc",
+    "fyi",
+    "ik",
+    "ikr",
+    "wyd",
+    "hbu",
+    "dm",
+    "gg",
+    "np",
+    "yw",
+    "k",
+    "kk",
+    "ofc",
+    "prolly",
+    "gonna",
+    "wanna",
+    "gotta",
+    "kinda",
+    "sorta",
+    "dunno",
+    "lemme",
+    "gimme",
+    "ya",
+    "yea",
+    "yep",
+    "nah",
+    "nope",
+]
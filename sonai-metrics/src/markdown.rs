@@ -0,0 +1,85 @@
+use pulldown_cmark::{Event, HeadingLevel, Tag, TagEnd};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Shape of the Markdown in a text, LLM output loves headed sections with bold lead-ins on every
+/// bullet.
+#[derive(Default)]
+pub(crate) struct MarkdownProfile {
+    pub headings: usize,
+    pub heading_depth: usize,
+    pub emoji_headings: usize,
+    pub list_items: usize,
+    pub list_depth: usize,
+    pub bold_lead_ins: usize,
+    pub tables: usize,
+    pub code_blocks: usize,
+    pub links: usize,
+
+    current_list_depth: usize,
+    heading_start: bool,
+    item_start: bool,
+}
+
+fn heading_level(level: HeadingLevel) -> usize {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+impl MarkdownProfile {
+    pub fn observe(&mut self, event: &Event) {
+        if self.heading_start {
+            self.heading_start = false;
+
+            if let Event::Text(text) = event
+                && text
+                    .trim_start()
+                    .graphemes(true)
+                    .next()
+                    .is_some_and(|g| emojis::get(g).is_some())
+            {
+                self.emoji_headings += 1;
+            }
+        }
+
+        if self.item_start {
+            match event {
+                // loose lists wrap items in paragraphs, look one event further
+                Event::Start(Tag::Paragraph) => {}
+                Event::Start(Tag::Strong) => {
+                    self.bold_lead_ins += 1;
+                    self.item_start = false;
+                }
+                _ => self.item_start = false,
+            }
+        }
+
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.headings += 1;
+                self.heading_depth = self.heading_depth.max(heading_level(*level));
+                self.heading_start = true;
+            }
+            Event::Start(Tag::List(_)) => {
+                self.current_list_depth += 1;
+                self.list_depth = self.list_depth.max(self.current_list_depth);
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.current_list_depth = self.current_list_depth.saturating_sub(1);
+            }
+            Event::Start(Tag::Item) => {
+                self.list_items += 1;
+                self.item_start = true;
+            }
+            Event::Start(Tag::Table(_)) => self.tables += 1,
+            Event::Start(Tag::CodeBlock(_)) => self.code_blocks += 1,
+            Event::Start(Tag::Link { .. }) => self.links += 1,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Absolute discount used at every order, the usual value for Kneser-Ney.
+const DISCOUNT: f64 = 0.75;
+
+/// Padding put before every text so the first characters still get a full context.
+const BOUNDARY: char = '\u{2}';
+
+/// Only the highest order counts are serialized, everything Kneser-Ney needs for the lower
+/// orders is rebuilt from them on load.
+#[derive(Serialize, Deserialize)]
+struct StoredNgramModel {
+    order: usize,
+    counts: Vec<(String, u32)>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Context {
+    total: u32,
+    distinct_followers: u32,
+}
+
+/// Interpolated Kneser-Ney character n-gram language model.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(try_from = "StoredNgramModel", into = "StoredNgramModel")]
+pub struct NgramModel {
+    order: usize,
+    // counts[k] holds n-grams of length k + 1, raw counts for the highest order and
+    // continuation counts (distinct left extensions) for the rest
+    counts: Vec<HashMap<String, u32>>,
+    // contexts[k] is keyed by the first k chars of the n-grams in counts[k]
+    contexts: Vec<HashMap<String, Context>>,
+    vocabulary: usize,
+}
+
+/// A stored model that can't be a model, from a corrupt or foreign `model.lm`.
+#[derive(Debug)]
+struct InvalidModel(String);
+
+impl fmt::Display for InvalidModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid n-gram model: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidModel {}
+
+impl TryFrom<StoredNgramModel> for NgramModel {
+    type Error = InvalidModel;
+
+    fn try_from(stored: StoredNgramModel) -> Result<Self, InvalidModel> {
+        Self::from_counts(stored.order, stored.counts.into_iter().collect())
+    }
+}
+
+impl From<NgramModel> for StoredNgramModel {
+    fn from(mut model: NgramModel) -> Self {
+        let mut counts: Vec<(String, u32)> = model
+            .counts
+            .pop()
+            .map(|highest| highest.into_iter().collect())
+            .unwrap_or_default();
+
+        // stable output, so retraining on the same data gives the same file
+        counts.sort_unstable();
+
+        Self {
+            order: model.order,
+            counts,
+        }
+    }
+}
+
+fn normalize(text: &str, order: usize) -> Vec<char> {
+    let mut chars: Vec<char> = std::iter::repeat_n(BOUNDARY, order.saturating_sub(1)).collect();
+
+    for word in text.split_whitespace() {
+        if chars.len() >= order {
+            chars.push(' ');
+        }
+        chars.extend(word.chars().flat_map(char::to_lowercase));
+    }
+
+    chars
+}
+
+impl NgramModel {
+    pub fn train<I, S>(texts: I, order: usize) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let order = order.max(1);
+        let mut counts: HashMap<String, u32> = HashMap::new();
+
+        for text in texts {
+            let chars = normalize(text.as_ref(), order);
+
+            for window in chars.windows(order) {
+                *counts.entry(window.iter().collect()).or_default() += 1;
+            }
+        }
+
+        Self::from_counts(order, counts).expect("training only counts n-grams of the model's order")
+    }
+
+    fn from_counts(order: usize, highest: HashMap<String, u32>) -> Result<Self, InvalidModel> {
+        if highest.is_empty() {
+            return Ok(Self::default());
+        }
+
+        if order == 0 {
+            return Err(InvalidModel("order 0 with n-grams".into()));
+        }
+
+        if let Some(ngram) = highest.keys().find(|ngram| ngram.chars().count() != order) {
+            return Err(InvalidModel(format!("{ngram:?} in an order {order} model")));
+        }
+
+        let mut counts = vec![HashMap::new(); order];
+        counts[order - 1] = highest;
+
+        // continuation count of an n-gram = number of distinct chars seen directly before it
+        for k in (1..order).rev() {
+            let mut lower: HashMap<String, u32> = HashMap::new();
+
+            for ngram in counts[k].keys() {
+                let mut chars = ngram.chars();
+                chars.next();
+                *lower.entry(chars.as_str().to_string()).or_default() += 1;
+            }
+
+            counts[k - 1] = lower;
+        }
+
+        let contexts = counts
+            .iter()
+            .map(|level| {
+                let mut contexts: HashMap<String, Context> = HashMap::new();
+
+                for (ngram, &count) in level {
+                    let (context, _) = split_last(ngram);
+                    let entry = contexts.entry(context.to_string()).or_default();
+                    entry.total += count;
+                    entry.distinct_followers += 1;
+                }
+
+                contexts
+            })
+            .collect();
+
+        let vocabulary = counts[0].len();
+
+        Ok(Self {
+            order,
+            counts,
+            contexts,
+            vocabulary,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Probability of the last char of `ngram` given the ones before it, `ngram` must have at most
+    /// `order` chars.
+    fn probability(&self, ngram: &str) -> f64 {
+        let k = ngram.chars().count() - 1;
+        let (context, _) = split_last(ngram);
+
+        let lower = if k == 0 {
+            // add-one floor so unseen chars don't produce infinite perplexity
+            1. / (self.vocabulary + 1) as f64
+        } else {
+            let mut chars = ngram.chars();
+            chars.next();
+            self.probability(chars.as_str())
+        };
+
+        let Some(stats) = self.contexts[k].get(context) else {
+            return lower;
+        };
+
+        let count = self.counts[k].get(ngram).copied().unwrap_or(0) as f64;
+        let total = stats.total as f64;
+        let backoff_weight = DISCOUNT * stats.distinct_followers as f64 / total;
+
+        (count - DISCOUNT).max(0.) / total + backoff_weight * lower
+    }
+
+    /// Sum of the log2 probabilities of every character and how many there were, `None` if the
+    /// model or the text is empty.
+    pub(crate) fn log_probability(&self, text: &str) -> Option<(f64, usize)> {
+        let mut scorer = self.scorer();
+        scorer.feed(text);
+        scorer.finish()
+    }
+
+    pub(crate) fn scorer(&self) -> Scorer<'_> {
+        Scorer {
+            model: self,
+            context: std::iter::repeat_n(BOUNDARY, self.order.saturating_sub(1)).collect(),
+            started: false,
+            sum: 0.,
+            n: 0,
+        }
+    }
+
+    /// Average log2 probability per character, `None` if the model or the text is empty.
+    pub fn average_log_probability(&self, text: &str) -> Option<f64> {
+        self.log_probability(text).map(|(sum, n)| sum / n as f64)
+    }
+
+    pub fn perplexity(&self, text: &str) -> Option<f64> {
+        self.average_log_probability(text).map(|lp| (-lp).exp2())
+    }
+}
+
+/// Scores text fed to it in pieces the same as [`NgramModel::log_probability`] scores all of it at
+/// once, as long as no word is split between two pieces.
+pub(crate) struct Scorer<'a> {
+    model: &'a NgramModel,
+    // the last order - 1 chars
+    context: Vec<char>,
+    started: bool,
+    sum: f64,
+    n: usize,
+}
+
+impl Scorer<'_> {
+    pub fn feed(&mut self, text: &str) {
+        if self.model.is_empty() {
+            return;
+        }
+
+        for word in text.split_whitespace() {
+            if std::mem::replace(&mut self.started, true) {
+                self.push(' ');
+            }
+
+            for c in word.chars().flat_map(char::to_lowercase) {
+                self.push(c);
+            }
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        let ngram: String = self.context.iter().chain([&c]).collect();
+        self.sum += self.model.probability(&ngram).log2();
+        self.n += 1;
+
+        if !self.context.is_empty() {
+            self.context.remove(0);
+            self.context.push(c);
+        }
+    }
+
+    /// Sum of the log2 probabilities and how many chars there were, `None` if the model or the
+    /// text was empty.
+    pub fn finish(&self) -> Option<(f64, usize)> {
+        (self.n > 0).then_some((self.sum, self.n))
+    }
+}
+
+fn split_last(ngram: &str) -> (&str, char) {
+    let mut chars = ngram.chars();
+    let last = chars.next_back().expect("n-grams are never empty");
+    (chars.as_str(), last)
+}
+
+/// A pair of language models, one trained on human text and one on AI text.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LanguageModels {
+    pub human: NgramModel,
+    pub ai: NgramModel,
+}
+
+impl LanguageModels {
+    /// Returns (perplexity under the human model, per-char log-likelihood ratio of AI over
+    /// human). Both are 0 when the models are empty.
+    pub fn score(&self, text: &str) -> (f64, f64) {
+        let (Some(human), Some(ai)) = (
+            self.human.average_log_probability(text),
+            self.ai.average_log_probability(text),
+        ) else {
+            return (0., 0.);
+        };
+
+        ((-human).exp2(), ai - human)
+    }
+}
@@ -0,0 +1,149 @@
+/// Zero width and formatting characters that chat UIs leave behind when text is copied out of
+/// them. ZWJ is left alone, emoji sequences need it.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{180E}'
+            | '\u{200B}'
+            | '\u{200C}'
+            | '\u{200E}'
+            | '\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
+
+/// Anything a keyboard doesn't type, non-breaking spaces, narrow no-break spaces, em spaces etc.
+fn is_unusual_whitespace(c: char) -> bool {
+    c.is_whitespace() && !matches!(c, ' ' | '\n' | '\r' | '\t')
+}
+
+enum Script {
+    Latin,
+    Other,
+    Confusable,
+}
+
+fn script(c: char) -> Option<Script> {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Some(Script::Latin),
+        // cyrillic + greek, the scripts with letters that look latin
+        '\u{0370}'..='\u{03FF}' | '\u{0400}'..='\u{04FF}' => Some(Script::Confusable),
+        c if c.is_alphabetic() => Some(Script::Other),
+        _ => None,
+    }
+}
+
+/// Latin lookalike of a cyrillic or greek letter, if it has one.
+fn homoglyph(c: char) -> Option<char> {
+    Some(match c {
+        'а' | 'α' => 'a',
+        'с' | 'ϲ' => 'c',
+        'е' | 'ε' => 'e',
+        'һ' => 'h',
+        'і' | 'ι' => 'i',
+        'ј' => 'j',
+        'к' | 'κ' => 'k',
+        'о' | 'ο' => 'o',
+        'р' | 'ρ' => 'p',
+        'ѕ' => 's',
+        'ν' => 'v',
+        'х' | 'χ' => 'x',
+        'у' | 'γ' => 'y',
+        'А' | 'Α' => 'A',
+        'В' | 'Β' => 'B',
+        'С' => 'C',
+        'Е' | 'Ε' => 'E',
+        'Н' | 'Η' => 'H',
+        'І' | 'Ι' => 'I',
+        'Ј' => 'J',
+        'К' | 'Κ' => 'K',
+        'М' | 'Μ' => 'M',
+        'О' | 'Ο' => 'O',
+        'Р' | 'Ρ' => 'P',
+        'Ѕ' => 'S',
+        'Т' | 'Τ' => 'T',
+        'Х' | 'Χ' => 'X',
+        'У' | 'Υ' => 'Y',
+        'Ζ' => 'Z',
+        _ => return None,
+    })
+}
+
+/// Words that mix latin letters with cyrillic or greek ones, `раssword` (cyrillic р) is, `пароль`
+/// isn't.
+fn is_mixed_script(word: &str) -> bool {
+    let mut latin = false;
+    let mut confusable = false;
+
+    for c in word.chars() {
+        match script(c) {
+            Some(Script::Latin) => latin = true,
+            Some(Script::Confusable) => confusable = true,
+            _ => {}
+        }
+    }
+
+    latin && confusable
+}
+
+/// Removes invisible characters, turns unusual whitespace into plain spaces and swaps homoglyphs
+/// in mixed-script words for their latin lookalikes, so none of them can break phrase matching.
+pub fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+
+    for word in text.split_inclusive(char::is_whitespace) {
+        let mixed = is_mixed_script(word);
+
+        for c in word.chars() {
+            if is_invisible(c) {
+                continue;
+            }
+
+            if is_unusual_whitespace(c) {
+                normalized.push(' ');
+            } else if mixed && let Some(latin) = homoglyph(c) {
+                normalized.push(latin);
+            } else {
+                normalized.push(c);
+            }
+        }
+    }
+
+    normalized
+}
+
+#[derive(Default)]
+pub(crate) struct Suspicious {
+    pub invisible: usize,
+    pub unusual_whitespace: usize,
+    pub confusables: usize,
+}
+
+/// Counts what [`normalize`] would clean up, confusables are counted per mixed-script word.
+pub(crate) fn suspicious_characters(text: &str) -> Suspicious {
+    let mut invisible = 0;
+    let mut unusual_whitespace = 0;
+
+    for c in text.chars() {
+        if is_invisible(c) {
+            invisible += 1;
+        } else if is_unusual_whitespace(c) {
+            unusual_whitespace += 1;
+        }
+    }
+
+    let confusables = text
+        .split(|c: char| c.is_whitespace())
+        .filter(|word| is_mixed_script(word))
+        .count();
+
+    Suspicious {
+        invisible,
+        unusual_whitespace,
+        confusables,
+    }
+}
@@ -0,0 +1,158 @@
+use aho_corasick::Anchored;
+use aho_corasick::automaton::{Automaton, StateID};
+use aho_corasick::dfa::DFA;
+
+/// Which metric a phrase list feeds.
+#[derive(Debug, Clone, Copy, Hash)]
+pub(crate) enum PhraseMetric {
+    Buzzword,
+    NegativeBuzzword,
+    NotJust,
+    Devlog,
+    Ellipsis,
+    Backstory,
+    NegativeBackstory,
+    IncorrectPerspective,
+    BrokenEnglish,
+    MrFancyPants,
+    Boilerplate,
+}
+
+const N_METRICS: usize = PhraseMetric::Boilerplate as usize + 1;
+
+/// Every phrase list in one automaton, the pattern ID says which metric a match counts for.
+#[derive(Debug)]
+pub(crate) struct Phrases {
+    dfa: DFA,
+    metrics: Vec<PhraseMetric>,
+}
+
+/// Every phrase list and the metric it feeds.
+pub(crate) fn lists() -> [(PhraseMetric, &'static [&'static str]); N_METRICS] {
+    [
+        (PhraseMetric::Buzzword, &include!("lists/buzzword.rs")),
+        (
+            PhraseMetric::NegativeBuzzword,
+            &include!("lists/negative_buzzword.rs"),
+        ),
+        (PhraseMetric::NotJust, &include!("lists/not_just.rs")),
+        (PhraseMetric::Devlog, &include!("lists/devlog.rs")),
+        (PhraseMetric::Ellipsis, &["…", "..."]),
+        (PhraseMetric::Backstory, &include!("lists/backstory.rs")),
+        (
+            PhraseMetric::NegativeBackstory,
+            &include!("lists/negative_backstory.rs"),
+        ),
+        (
+            PhraseMetric::IncorrectPerspective,
+            &include!("lists/incorrect_perspective.rs"),
+        ),
+        (
+            PhraseMetric::BrokenEnglish,
+            &include!("lists/broken_english.rs"),
+        ),
+        (
+            PhraseMetric::MrFancyPants,
+            &["(e.g.", "(formerly", "role- "],
+        ),
+        (PhraseMetric::Boilerplate, &include!("lists/boilerplate.rs")),
+    ]
+}
+
+impl Phrases {
+    pub fn new() -> Result<Self, aho_corasick::BuildError> {
+        let mut patterns = Vec::new();
+        let mut metrics = Vec::new();
+
+        for (metric, list) in lists() {
+            // the lists used to be matched against lowercased text, so a pattern with an uppercase
+            // letter (` :D`) never matched and the deployed model was trained without it
+            for &pattern in list {
+                if !pattern.bytes().any(|b| b.is_ascii_uppercase()) {
+                    patterns.push(pattern);
+                    metrics.push(metric);
+                }
+            }
+        }
+
+        // overlapping search needs standard semantics, which is also what the lists were written for
+        let dfa = DFA::builder()
+            .ascii_case_insensitive(true)
+            .build(&patterns)?;
+
+        Ok(Self { dfa, metrics })
+    }
+
+    pub fn scanner(&self) -> PhraseScanner<'_> {
+        PhraseScanner {
+            phrases: self,
+            state: self.dfa.start_state(Anchored::No).expect("unanchored dfa"),
+            position: 0,
+            last_end: [0; N_METRICS],
+            counts: [0; N_METRICS],
+            started: false,
+            pending_space: false,
+        }
+    }
+}
+
+/// Counts phrases in text fed to it in pieces, a phrase can span two pieces. Runs of spaces and
+/// newlines match as a single space and leading/trailing ones are ignored.
+pub(crate) struct PhraseScanner<'a> {
+    phrases: &'a Phrases,
+    state: StateID,
+    position: usize,
+    // matches of one list don't overlap, same as a separate find_iter per list
+    last_end: [usize; N_METRICS],
+    counts: [usize; N_METRICS],
+    started: bool,
+    pending_space: bool,
+}
+
+impl PhraseScanner<'_> {
+    pub fn feed(&mut self, text: &str) {
+        for c in text.chars() {
+            if matches!(c, ' ' | '\n') {
+                self.pending_space = self.started;
+                continue;
+            }
+
+            if std::mem::take(&mut self.pending_space) {
+                self.step(b' ');
+            }
+
+            let mut buffer = [0; 4];
+            for &byte in c.encode_utf8(&mut buffer).as_bytes() {
+                self.step(byte);
+            }
+
+            self.started = true;
+        }
+    }
+
+    fn step(&mut self, byte: u8) {
+        let dfa = &self.phrases.dfa;
+
+        self.state = dfa.next_state(Anchored::No, self.state, byte);
+        self.position += 1;
+
+        if !dfa.is_match(self.state) {
+            return;
+        }
+
+        for i in 0..dfa.match_len(self.state) {
+            let pattern = dfa.match_pattern(self.state, i);
+            let metric = self.phrases.metrics[pattern.as_usize()] as usize;
+            let start = self.position - dfa.pattern_len(pattern);
+
+            if start >= self.last_end[metric] {
+                self.counts[metric] += 1;
+                self.last_end[metric] = self.position;
+            }
+        }
+    }
+
+    pub fn count(&self, metric: PhraseMetric) -> usize {
+        self.counts[metric as usize]
+    }
+}
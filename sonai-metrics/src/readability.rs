@@ -0,0 +1,93 @@
+pub(crate) struct Readability {
+    pub flesch_reading_ease: f64,
+    pub flesch_kincaid_grade: f64,
+    pub gunning_fog: f64,
+    pub smog: f64,
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// Rough English syllable count, vowel groups minus the usual silent endings. Good to about one
+/// syllable on most words, which is all the readability formulas need.
+pub(crate) fn syllables(word: &str) -> usize {
+    let word = word.trim_end_matches(['\'', '’']);
+    let chars: Vec<char> = word.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+    if chars.len() <= 3 {
+        return 1;
+    }
+
+    let mut count = 0;
+    let mut previous_vowel = false;
+
+    for &c in &chars {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+
+    let n = chars.len();
+    let consonant_before = |i: usize| !is_vowel(chars[i]);
+
+    // make, shoes, baked are one syllable but table, wanted, boxes are two
+    let silent_e = chars[n - 1] == 'e' && consonant_before(n - 2) && chars[n - 2] != 'l';
+    let silent_suffix = matches!((chars[n - 2], chars[n - 1]), ('e', 's') | ('e', 'd'))
+        && consonant_before(n - 3)
+        && !matches!(chars[n - 3], 't' | 'd' | 's' | 'x' | 'z' | 'c' | 'g');
+
+    if silent_e || silent_suffix {
+        count -= 1;
+    }
+
+    count.max(1)
+}
+
+/// Word, syllable and polysyllable counts, only alphabetic words should be pushed, numbers and
+/// code would otherwise skew the grade.
+#[derive(Default)]
+pub(crate) struct ReadabilityCounts {
+    words: usize,
+    syllables: usize,
+    polysyllables: usize,
+}
+
+impl ReadabilityCounts {
+    pub fn push(&mut self, word: &str) {
+        let s = syllables(word);
+
+        self.words += 1;
+        self.syllables += s;
+        if s >= 3 {
+            self.polysyllables += 1;
+        }
+    }
+
+    pub fn finish(&self, sentences: usize) -> Readability {
+        if self.words == 0 {
+            return Readability {
+                flesch_reading_ease: 0.,
+                flesch_kincaid_grade: 0.,
+                gunning_fog: 0.,
+                smog: 0.,
+            };
+        }
+
+        let sentences = sentences.max(1) as f64;
+        let n = self.words as f64;
+
+        let words_per_sentence = n / sentences;
+        let syllables_per_word = self.syllables as f64 / n;
+        let polysyllables = self.polysyllables as f64;
+
+        Readability {
+            flesch_reading_ease: 206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word,
+            flesch_kincaid_grade: 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59,
+            gunning_fog: 0.4 * (words_per_sentence + 100. * polysyllables / n),
+            smog: 1.043 * (polysyllables * 30. / sentences).sqrt() + 3.1291,
+        }
+    }
+}
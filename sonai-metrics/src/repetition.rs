@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+/// Formulaic text reuses the same phrases and sentence frames ("I implemented X. I implemented
+/// Y."), spam repeats the same word ("vote vote vote").
+#[derive(Default)]
+pub(crate) struct Repetition {
+    previous: [String; 2],
+    words: usize,
+    trigrams: HashMap<String, usize>,
+    adjacent: usize,
+    openers: HashMap<String, usize>,
+    sentences: usize,
+}
+
+pub(crate) struct Repeats {
+    pub trigram_rate: f64,
+    pub opener_rate: f64,
+    pub adjacent: usize,
+}
+
+/// Share of the occurrences that belong to something seen more than once.
+fn repeated_share(counts: &HashMap<String, usize>, total: usize) -> f64 {
+    let repeated: usize = counts.values().filter(|&&c| c > 1).sum();
+    repeated as f64 / total.max(1) as f64
+}
+
+impl Repetition {
+    pub fn push_word(&mut self, word: &str) {
+        let word = word.to_lowercase();
+
+        if word == self.previous[1] {
+            self.adjacent += 1;
+        }
+
+        if self.words >= 2 {
+            let trigram = format!("{} {} {word}", self.previous[0], self.previous[1]);
+            *self.trigrams.entry(trigram).or_default() += 1;
+        }
+
+        self.words += 1;
+        self.previous.rotate_left(1);
+        self.previous[1] = word;
+    }
+
+    /// The first two words are the opener.
+    pub fn push_sentence(&mut self, words: &[&str]) {
+        if words.is_empty() {
+            return;
+        }
+
+        let opener = words[..words.len().min(2)].join(" ").to_lowercase();
+        *self.openers.entry(opener).or_default() += 1;
+        self.sentences += 1;
+    }
+
+    pub fn finish(&self) -> Repeats {
+        Repeats {
+            trigram_rate: repeated_share(&self.trigrams, self.words.saturating_sub(2)),
+            opener_rate: repeated_share(&self.openers, self.sentences),
+            adjacent: self.adjacent,
+        }
+    }
+}
+
+/// LZ78 compression estimate, the text is parsed into phrases that each extend an earlier one by a
+/// byte. Repetitive text needs few long phrases.
+#[derive(Default)]
+pub(crate) struct Compression {
+    phrases: HashMap<(u32, u8), u32>,
+    current: u32, // 0 is the empty phrase
+    bytes: usize,
+    emitted: usize,
+}
+
+impl Compression {
+    pub fn feed(&mut self, text: &str) {
+        for &byte in text.as_bytes() {
+            self.bytes += 1;
+
+            match self.phrases.get(&(self.current, byte)) {
+                Some(&next) => self.current = next,
+                None => {
+                    let id = self.phrases.len() as u32 + 1;
+                    self.phrases.insert((self.current, byte), id);
+                    self.emitted += 1;
+                    self.current = 0;
+                }
+            }
+        }
+    }
+
+    /// Estimated compressed size / raw size, 0 for empty text.
+    pub fn ratio(&self) -> f64 {
+        if self.bytes == 0 {
+            return 0.;
+        }
+
+        // an unfinished phrase at the end still has to be written out
+        let phrases = self.emitted + usize::from(self.current != 0);
+
+        // each phrase is a reference to an earlier one plus a byte
+        let bits = phrases as f64 * ((phrases as f64).log2().ceil() + 8.);
+
+        bits / (self.bytes * 8) as f64
+    }
+}
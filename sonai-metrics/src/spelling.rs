@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+/// Longer words are identifiers, URLs and keyboard mashing, not typos, and looking for a word one
+/// edit away gets expensive.
+const MAX_TYPO_LEN: usize = 20;
+
+/// What makes a word informal, each word counts for at most one.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Informal {
+    Texting,           // idk, tbh, u
+    MissingApostrophe, // dont, im
+    Elongation,        // sooo, yesss
+    Misspelling,       // teh, recieve
+}
+
+/// Classifies words against embedded lists. Texting abbreviations, missing apostrophes and known
+/// misspellings are looked up in their own lists. Any other lowercase word of 4 to 20 letters
+/// that isn't in the dictionary but is one edit away from a word that is counts as a
+/// misspelling. The dictionary has every inflection (enemies, swept, tweaking), words are looked
+/// up as written. `lists/english.md` says where it comes from.
+#[derive(Debug)]
+pub(crate) struct Spelling {
+    dictionary: HashSet<&'static str>,
+    misspellings: HashSet<&'static str>,
+    texting: HashSet<&'static str>,
+    missing_apostrophes: HashSet<&'static str>,
+}
+
+fn is_elongated(word: &str) -> bool {
+    let mut run = 0;
+    let mut previous = None;
+
+    for c in word.chars() {
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        previous = Some(c);
+
+        if run >= 3 {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub(crate) const DICTIONARY: &str = include_str!("lists/english.txt");
+pub(crate) const PROGRAMMING: &str = include_str!("lists/programming.txt");
+pub(crate) const MISSPELLINGS: &[&str] = &include!("lists/misspellings.rs");
+pub(crate) const TEXTING: &[&str] = &include!("lists/texting.rs");
+pub(crate) const MISSING_APOSTROPHES: &[&str] = &include!("lists/missing_apostrophe.rs");
+
+impl Spelling {
+    pub fn new() -> Self {
+        Self {
+            dictionary: DICTIONARY.lines().chain(PROGRAMMING.lines()).collect(),
+            misspellings: MISSPELLINGS.iter().copied().collect(),
+            texting: TEXTING.iter().copied().collect(),
+            missing_apostrophes: MISSING_APOSTROPHES.iter().copied().collect(),
+        }
+    }
+
+    fn is_known(&self, word: &str) -> bool {
+        self.dictionary.contains(word)
+            || word
+                .strip_suffix("'s")
+                .is_some_and(|word| self.dictionary.contains(word))
+    }
+
+    /// A word one deletion, transposition, substitution or insertion away from `word` (lowercase
+    /// ascii) is in the word list. Edits are made in place, one buffer for all of them.
+    fn is_near_known(&self, word: &str) -> bool {
+        let word = word.as_bytes();
+        let n = word.len();
+        let mut edit = Vec::with_capacity(n + 1);
+        let known = |edit: &[u8]| {
+            std::str::from_utf8(edit).is_ok_and(|edit| self.dictionary.contains(edit))
+        };
+
+        for i in 0..n {
+            edit.clear();
+            edit.extend_from_slice(&word[..i]);
+            edit.extend_from_slice(&word[i + 1..]);
+
+            if known(&edit) {
+                return true;
+            }
+        }
+
+        edit.clear();
+        edit.extend_from_slice(word);
+
+        for i in 1..n {
+            edit.swap(i - 1, i);
+
+            if known(&edit) {
+                return true;
+            }
+
+            edit.swap(i - 1, i);
+        }
+
+        for i in 0..n {
+            for c in b'a'..=b'z' {
+                edit[i] = c;
+
+                if known(&edit) {
+                    return true;
+                }
+            }
+
+            edit[i] = word[i];
+        }
+
+        for i in 0..=n {
+            edit.clear();
+            edit.extend_from_slice(&word[..i]);
+            edit.push(b'a');
+            edit.extend_from_slice(&word[i..]);
+
+            for c in b'a'..=b'z' {
+                edit[i] = c;
+
+                if known(&edit) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn classify(&self, word: &str) -> Option<Informal> {
+        let lowercase = word.to_lowercase().replace('’', "'");
+
+        if self.texting.contains(lowercase.as_str()) {
+            return Some(Informal::Texting);
+        }
+
+        if self.missing_apostrophes.contains(lowercase.as_str()) {
+            return Some(Informal::MissingApostrophe);
+        }
+
+        // teh, untill, recieve, flagged even when short or capitalized
+        if self.misspellings.contains(lowercase.as_str()) {
+            return Some(Informal::Misspelling);
+        }
+
+        if !lowercase
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c == '\'')
+        {
+            return None;
+        }
+
+        if is_elongated(&lowercase) && !self.is_known(&lowercase) {
+            return Some(Informal::Elongation);
+        }
+
+        // capitalized words are names and acronyms more often than typos, and short words are
+        // names and abbreviations
+        if word.chars().any(char::is_uppercase)
+            || lowercase.len() < 4
+            || lowercase.len() > MAX_TYPO_LEN
+            || self.is_known(&lowercase)
+            || !self.is_near_known(&lowercase)
+        {
+            return None;
+        }
+
+        Some(Informal::Misspelling)
+    }
+}
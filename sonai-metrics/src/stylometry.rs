@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Window size for the moving-average type-token ratio. Plain TTR falls as texts get longer, MATTR
+/// doesn't.
+const MATTR_WINDOW: usize = 50;
+
+/// Running mean and (population) variance.
+#[derive(Default)]
+pub(crate) struct MeanVariance {
+    n: usize,
+    sum: f64,
+    sum_squares: f64,
+}
+
+impl MeanVariance {
+    pub fn push(&mut self, value: usize) {
+        let value = value as f64;
+        self.n += 1;
+        self.sum += value;
+        self.sum_squares += value * value;
+    }
+
+    pub fn finish(&self) -> (f64, f64) {
+        if self.n == 0 {
+            return (0., 0.);
+        }
+
+        let n = self.n as f64;
+        let mean = self.sum / n;
+
+        // rounding can push it a hair below 0 for identical values
+        (mean, (self.sum_squares / n - mean * mean).max(0.))
+    }
+}
+
+/// Type-token ratio, MATTR, hapax legomena ratio and average word length over a stream of words.
+#[derive(Default)]
+pub(crate) struct LexicalDiversity {
+    frequencies: HashMap<String, usize>,
+    words: usize,
+    chars: usize,
+
+    // slide the window, tracking the number of distinct words inside it
+    window: VecDeque<String>,
+    window_frequencies: HashMap<String, usize>,
+    window_total: usize,
+}
+
+pub(crate) struct Lexical {
+    pub type_token_ratio: f64,
+    pub mattr: f64,
+    pub hapax_ratio: f64,
+    pub avg_word_length: f64,
+}
+
+impl LexicalDiversity {
+    /// Case is ignored.
+    pub fn push(&mut self, word: &str) {
+        let word = word.to_ascii_lowercase();
+
+        self.words += 1;
+        self.chars += word.chars().count();
+        *self.frequencies.entry(word.clone()).or_default() += 1;
+
+        if self.window.len() == MATTR_WINDOW
+            && let Some(outgoing) = self.window.pop_front()
+            && let Some(count) = self.window_frequencies.get_mut(&outgoing)
+        {
+            *count -= 1;
+            if *count == 0 {
+                self.window_frequencies.remove(&outgoing);
+            }
+        }
+
+        *self.window_frequencies.entry(word.clone()).or_default() += 1;
+        self.window.push_back(word);
+
+        if self.window.len() == MATTR_WINDOW {
+            self.window_total += self.window_frequencies.len();
+        }
+    }
+
+    pub fn words(&self) -> usize {
+        self.words
+    }
+
+    pub fn finish(&self) -> Lexical {
+        if self.words == 0 {
+            return Lexical {
+                type_token_ratio: 0.,
+                mattr: 0.,
+                hapax_ratio: 0.,
+                avg_word_length: 0.,
+            };
+        }
+
+        let n = self.words as f64;
+        let ttr = self.frequencies.len() as f64 / n;
+        let hapax = self.frequencies.values().filter(|&&c| c == 1).count() as f64 / n;
+
+        let mattr = if self.words <= MATTR_WINDOW {
+            ttr
+        } else {
+            let windows = (self.words - MATTR_WINDOW + 1) as f64;
+            self.window_total as f64 / windows / MATTR_WINDOW as f64
+        };
+
+        Lexical {
+            type_token_ratio: ttr,
+            mattr,
+            hapax_ratio: hapax,
+            avg_word_length: self.chars as f64 / n,
+        }
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    // ascii + general punctuation block, which covers the fancy dashes, quotes and ellipses
+    c.is_ascii_punctuation() || ('\u{2010}'..='\u{205E}').contains(&c)
+}
+
+#[derive(Default)]
+pub(crate) struct Punctuation {
+    // ordered, so the entropy sums the same way every run
+    frequencies: BTreeMap<char, usize>,
+}
+
+impl Punctuation {
+    pub fn observe(&mut self, text: &str) {
+        for c in text.chars().filter(|&c| is_punctuation(c)) {
+            *self.frequencies.entry(c).or_default() += 1;
+        }
+    }
+
+    /// Shannon entropy (bits) of the punctuation marks used. A text that only ever uses `.` and
+    /// `,` scores low, one that mixes `;`, `—`, `(`, `!` etc scores high.
+    pub fn diversity(&self) -> f64 {
+        let total = self.frequencies.values().sum::<usize>() as f64;
+
+        self.frequencies
+            .values()
+            .map(|&c| {
+                let p = c as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,68 @@
+/// Headings and labels every README scaffold and LLM project description has.
+pub(crate) const SECTIONS: &[&str] = &include!("lists/template_sections.rs");
+
+const LONGEST_SECTION: usize = {
+    let mut longest = 0;
+    let mut i = 0;
+
+    while i < SECTIONS.len() {
+        if SECTIONS[i].len() > longest {
+            longest = SECTIONS[i].len();
+        }
+        i += 1;
+    }
+
+    longest
+};
+
+/// Just the words of a heading or label, `## 🚀 1. Tech Stack:` -> `tech stack`, built a char at a
+/// time. Stops growing once it's longer than any section, so a whole line can be pushed.
+#[derive(Default)]
+pub(crate) struct SectionName {
+    name: String,
+    space: bool,
+    too_long: bool,
+}
+
+impl SectionName {
+    pub fn push(&mut self, c: char) {
+        if self.too_long {
+            return;
+        }
+
+        if c.is_whitespace() {
+            self.space = !self.name.is_empty();
+            return;
+        }
+
+        if !c.is_alphabetic() && !matches!(c, '\'' | '’') {
+            return;
+        }
+
+        if std::mem::take(&mut self.space) {
+            self.name.push(' ');
+        }
+
+        match c {
+            '’' => self.name.push('\''),
+            c => self.name.extend(c.to_lowercase()),
+        }
+
+        self.too_long = self.name.len() > LONGEST_SECTION;
+    }
+
+    /// The name, unless it got too long to be a section.
+    pub fn as_str(&self) -> Option<&str> {
+        (!self.too_long).then_some(self.name.as_str())
+    }
+
+    pub fn is_template_section(&self) -> bool {
+        self.as_str().is_some_and(|name| SECTIONS.contains(&name))
+    }
+}
+
+pub(crate) fn is_template_section(text: &str) -> bool {
+    let mut name = SectionName::default();
+    text.chars().for_each(|c| name.push(c));
+    name.is_template_section()
+}
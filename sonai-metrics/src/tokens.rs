@@ -0,0 +1,201 @@
+use crate::code::is_code_token;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Url,
+    Email,
+    Mention,   // @someone
+    IssueRef,  // #42
+    DevlogRef, // the 3 in devlog #3, already counted by devlog_count
+    Hashtag,   // #rust
+    Number,    // 42 3.5 1,000 50% $20
+    Code,
+    Word,
+}
+
+/// Words after which `#n` numbers a devlog instead of referencing an issue.
+const DEVLOG_WORDS: &[&str] = &["devlog", "dev-log", "log", "day", "update", "part", "entry"];
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_number(token: &str) -> bool {
+    let token = token.trim_start_matches(['$', '€', '£', '+', '-']);
+    let token = token.trim_end_matches(['%', 'k', 'x']);
+
+    token.starts_with(|c: char| c.is_ascii_digit()) && token.replace(',', "").parse::<f64>().is_ok()
+}
+
+/// `previous` is the token before, lowercase and without punctuation.
+pub(crate) fn classify(token: &str, previous: &str) -> TokenKind {
+    let lowercase = token.to_ascii_lowercase();
+
+    if lowercase.contains("://") || lowercase.starts_with("www.") {
+        return TokenKind::Url;
+    }
+
+    if let Some((user, domain)) = token.split_once('@')
+        && !user.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+    {
+        return TokenKind::Email;
+    }
+
+    if let Some(handle) = token.strip_prefix('@')
+        && !handle.is_empty()
+        && handle
+            .chars()
+            .all(|c| is_word_char(c) || matches!(c, '.' | '-'))
+    {
+        return TokenKind::Mention;
+    }
+
+    // exactly one #, ## and ### are heading fragments not hashtags
+    if let Some(tag) = token.strip_prefix('#')
+        && tag.starts_with(is_word_char)
+        && tag.chars().all(is_word_char)
+    {
+        if !tag.chars().all(|c| c.is_ascii_digit()) {
+            return TokenKind::Hashtag;
+        }
+
+        return if DEVLOG_WORDS.contains(&previous) {
+            TokenKind::DevlogRef
+        } else {
+            TokenKind::IssueRef
+        };
+    }
+
+    if is_number(token) {
+        return TokenKind::Number;
+    }
+
+    if is_code_token(token) {
+        return TokenKind::Code;
+    }
+
+    TokenKind::Word
+}
+
+/// Classifies every token, counting each in exactly one bucket. Code is removed and urls and
+/// emails are replaced with a placeholder word so they can't count as dashes, arrows, hashtags
+/// or sentence ends, sentence punctuation after them is kept.
+///
+/// Text is fed in pieces and a token can span two of them, the prose is written out as tokens
+/// finish.
+#[derive(Default)]
+pub(crate) struct Tokenizer {
+    pub code_tokens: usize,
+    pub code_chars: usize,
+    pub urls: usize,
+    pub emails: usize,
+    pub mentions: usize,
+    pub issue_refs: usize,
+    pub hashtags: usize,
+    pub numbers: usize,
+
+    token: String,
+    // the token before on the same line, lowercase and without punctuation
+    previous: String,
+    line_started: bool,
+}
+
+impl Tokenizer {
+    pub fn feed(&mut self, text: &str, prose: &mut String) {
+        let mut rest = text;
+
+        while let Some(end) = rest.find([' ', '\n']) {
+            self.token.push_str(&rest[..end]);
+            self.end_token(prose);
+
+            if rest.as_bytes()[end] == b'\n' {
+                prose.push('\n');
+                self.previous.clear();
+                self.line_started = false;
+            }
+
+            rest = &rest[end + 1..];
+        }
+
+        self.token.push_str(rest);
+    }
+
+    /// Writes out the last token.
+    pub fn finish(&mut self, prose: &mut String) {
+        self.end_token(prose);
+    }
+
+    fn end_token(&mut self, prose: &mut String) {
+        let token = std::mem::take(&mut self.token);
+
+        let without_trailing =
+            token.trim_end_matches(['.', ',', '!', '?', ':', ';', ')', '"', '\'']);
+        let core = without_trailing.trim_start_matches(['(', '"', '\'']);
+        let trailing = &token[without_trailing.len()..];
+
+        let kind = if core.is_empty() {
+            TokenKind::Word
+        } else {
+            classify(core, &self.previous)
+        };
+
+        let replacement = match kind {
+            TokenKind::Url => {
+                self.urls += 1;
+                Some("url")
+            }
+            TokenKind::Email => {
+                self.emails += 1;
+                Some("email")
+            }
+            TokenKind::Code => {
+                self.code_tokens += 1;
+                self.code_chars += core.chars().count();
+                Some("")
+            }
+            TokenKind::Mention => {
+                self.mentions += 1;
+                None
+            }
+            TokenKind::IssueRef => {
+                self.issue_refs += 1;
+                None
+            }
+            TokenKind::Hashtag => {
+                self.hashtags += 1;
+                None
+            }
+            TokenKind::Number => {
+                self.numbers += 1;
+                None
+            }
+            TokenKind::DevlogRef | TokenKind::Word => None,
+        };
+
+        self.previous = core
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '-')
+            .to_lowercase();
+
+        let (word, trailing) = match replacement {
+            Some(word) => (word, trailing),
+            None => (token.as_str(), ""),
+        };
+
+        if !word.is_empty() || !trailing.is_empty() {
+            if self.line_started {
+                prose.push(' ');
+            }
+
+            prose.push_str(word);
+            prose.push_str(trailing);
+            self.line_started = true;
+        }
+
+        // keep the allocation for the next token
+        self.token = token;
+        self.token.clear();
+    }
+}
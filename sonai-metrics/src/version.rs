@@ -0,0 +1,65 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{
+    InputFormat, LANGUAGE_MODEL_FEATURES, N_FEATURES, TextMetricFactory, TextMetrics, phrases,
+    spelling, template,
+};
+
+/// Bump when a metric is computed differently without any list or the feature layout changing,
+/// `tests/version.rs` fails when the golden snapshots change and the version doesn't.
+const REVISION: u32 = 5;
+
+/// FNV-1a, unlike `DefaultHasher` it gives the same hash with every Rust version.
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl TextMetricFactory {
+    /// Changes whenever the metrics this factory computes could: the phrase and word lists, the
+    /// emoji sets, the input format or the feature layout. Metrics cached under one version can
+    /// be reused for the same text as long as it stays the same.
+    ///
+    /// The language models aren't included, hash them separately.
+    pub fn version(&self) -> String {
+        let mut hasher = Fnv(0xcbf29ce484222325);
+
+        REVISION.hash(&mut hasher);
+        N_FEATURES.hash(&mut hasher);
+        LANGUAGE_MODEL_FEATURES.hash(&mut hasher);
+        size_of::<TextMetrics>().hash(&mut hasher);
+
+        phrases::lists().hash(&mut hasher);
+        spelling::DICTIONARY.hash(&mut hasher);
+        spelling::PROGRAMMING.hash(&mut hasher);
+        spelling::MISSPELLINGS.hash(&mut hasher);
+        spelling::TEXTING.hash(&mut hasher);
+        spelling::MISSING_APOSTROPHES.hash(&mut hasher);
+        template::SECTIONS.hash(&mut hasher);
+
+        for emojis in [&self.ai_emojis, &self.human_emojis] {
+            let mut emojis: Vec<&String> = emojis.iter().collect();
+            emojis.sort_unstable();
+            emojis.hash(&mut hasher);
+        }
+
+        match self.input_format {
+            InputFormat::Plain => 0u8,
+            InputFormat::Markdown => 1,
+            InputFormat::Html => 2,
+        }
+        .hash(&mut hasher);
+
+        format!("{:016x}", hasher.finish())
+    }
+}
@@ -0,0 +1,85 @@
+//! Time budgets for `calculate`, the demo runs it on every keystroke. `benches/metrics.rs` has
+//! the numbers, these only fail when a change makes it several times slower. Timings depend on
+//! the machine, so they're ignored by default and meant for release builds on a quiet one:
+//!
+//! ```sh
+//! cargo test --release -p sonai_metrics --test budget -- --ignored
+//! ```
+
+use std::time::{Duration, Instant};
+
+use sonai_metrics::{InputFormat, TextMetricFactory};
+
+/// Fastest of a few runs, a busy machine only ever makes a run slower
+fn fastest(text: &str) -> Duration {
+    if cfg!(debug_assertions) {
+        panic!("the budgets are for release builds, run with --release");
+    }
+
+    let factory = TextMetricFactory::new().unwrap();
+
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(factory.calculate(std::hint::black_box(text)));
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn medium() -> String {
+    [
+        include_str!("golden/ai_markdown.md"),
+        include_str!("golden/code_heavy.md"),
+        include_str!("golden/human_casual.md"),
+        include_str!("golden/readme_template.md"),
+        include_str!("golden/unicode_paste.md"),
+    ]
+    .join("\n\n")
+}
+
+/// Words no list has, every one of them goes through the whole spell check
+fn unknown_words(count: usize) -> String {
+    const SYLLABLES: [&str; 8] = ["zor", "bli", "quan", "dre", "vux", "tham", "pel", "gow"];
+
+    (0..count)
+        .map(|i| {
+            (0..3)
+                .map(|digit| SYLLABLES[i / SYLLABLES.len().pow(digit) % SYLLABLES.len()])
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+#[ignore = "timing, run in release with --ignored"]
+fn medium_text_within_budget() {
+    let elapsed = fastest(&medium());
+
+    assert!(elapsed < Duration::from_millis(5), "took {elapsed:?}");
+}
+
+#[test]
+#[ignore = "timing, run in release with --ignored"]
+fn unknown_words_within_budget() {
+    let elapsed = fastest(&unknown_words(2000));
+
+    assert!(elapsed < Duration::from_millis(100), "took {elapsed:?}");
+}
+
+#[test]
+#[ignore = "timing, run in release with --ignored"]
+fn ampersands_within_budget() {
+    let factory = TextMetricFactory::new()
+        .unwrap()
+        .with_input_format(InputFormat::Html);
+    let text = format!("<p>{}</p>", "&a".repeat(50_000));
+
+    let start = Instant::now();
+    std::hint::black_box(factory.calculate(&text));
+    let elapsed = start.elapsed();
+
+    assert!(elapsed < Duration::from_millis(100), "took {elapsed:?}");
+}
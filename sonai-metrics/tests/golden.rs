@@ -0,0 +1,147 @@
+//! Golden snapshots of `TextMetricFactory::calculate`, a deployed model depends on every one of
+//! these values so a list edit or refactor that changes them should be deliberate.
+//!
+//! Inputs live in `tests/golden/`, the extension picks the input format (`.md`, `.html`, `.txt`).
+//! After an intended change regenerate the snapshots with
+//!
+//! ```sh
+//! UPDATE_GOLDEN=1 cargo test -p sonai_metrics --test golden
+//! ```
+//!
+//! and review the diff of the `.snap` files.
+
+use serde_json::{Map, Value};
+use sonai_metrics::{InputFormat, TextMetricFactory};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TOLERANCE: f64 = 1e-9;
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn input_format(path: &Path) -> Option<InputFormat> {
+    match path.extension()?.to_str()? {
+        "md" => Some(InputFormat::Markdown),
+        "html" => Some(InputFormat::Html),
+        "txt" => Some(InputFormat::Plain),
+        _ => None,
+    }
+}
+
+fn snapshot(path: &Path, format: InputFormat) -> Map<String, Value> {
+    let text = fs::read_to_string(path).unwrap();
+    let metrics = TextMetricFactory::new()
+        .unwrap()
+        .with_input_format(format)
+        .calculate(&text);
+
+    match serde_json::to_value(&metrics).unwrap() {
+        Value::Object(map) => map,
+        _ => unreachable!("TextMetrics serializes to an object"),
+    }
+}
+
+fn differences(expected: &Map<String, Value>, actual: &Map<String, Value>) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    for (metric, value) in actual {
+        match (expected.get(metric).and_then(Value::as_f64), value.as_f64()) {
+            (None, _) => differences.push(format!("{metric}: new metric, {value}")),
+            (Some(expected), Some(actual))
+                if (expected - actual).abs() <= TOLERANCE * expected.abs().max(1.) => {}
+            (Some(expected), _) => differences.push(format!("{metric}: {expected} -> {value}")),
+        }
+    }
+
+    for metric in expected
+        .keys()
+        .filter(|metric| !actual.contains_key(*metric))
+    {
+        differences.push(format!("{metric}: removed"));
+    }
+
+    differences
+}
+
+#[test]
+fn golden() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    let mut inputs: Vec<PathBuf> = fs::read_dir(golden_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    inputs.sort();
+
+    for input in inputs {
+        let Some(format) = input_format(&input) else {
+            continue;
+        };
+
+        let actual = snapshot(&input, format);
+        let snap = input.with_extension(format!(
+            "{}.snap",
+            input.extension().unwrap().to_str().unwrap()
+        ));
+
+        if update {
+            let json = serde_json::to_string_pretty(&actual).unwrap();
+            fs::write(&snap, json + "\n").unwrap();
+            continue;
+        }
+
+        let Ok(expected) = fs::read_to_string(&snap) else {
+            failures.push(format!("{}: no snapshot", input.display()));
+            continue;
+        };
+
+        let expected: Map<String, Value> = serde_json::from_str(&expected).unwrap();
+
+        for difference in differences(&expected, &actual) {
+            failures.push(format!("{}: {difference}", input.display()));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "metrics changed, rerun with UPDATE_GOLDEN=1 if this is intended:\n{}",
+        failures.join("\n")
+    );
+}
+
+/// Each typo planted in `typos.md` is caught, and nothing else in it is.
+#[test]
+fn planted_typos_are_detected() {
+    const TYPOS: &[(&str, &str)] = &[("finaly", "finally"), ("enemys", "enemies"), ("teh", "the")];
+
+    let factory = TextMetricFactory::new().unwrap();
+    let text = fs::read_to_string(golden_dir().join("typos.md")).unwrap();
+    let fix = |except: Option<&str>| {
+        TYPOS
+            .iter()
+            .filter(|(typo, _)| Some(*typo) != except)
+            .fold(text.clone(), |text, (typo, fixed)| {
+                text.replace(typo, fixed)
+            })
+    };
+
+    assert_eq!(factory.calculate(&fix(None)).misspelling_rate, 0.0);
+
+    // the fixes don't change the word count, so each typo adds the same rate
+    let one = factory.calculate(&fix(Some(TYPOS[0].0))).misspelling_rate;
+    assert!(one > 0.0);
+
+    for (typo, _) in TYPOS {
+        assert_eq!(
+            factory.calculate(&fix(Some(typo))).misspelling_rate,
+            one,
+            "{typo}"
+        );
+    }
+
+    let all = factory.calculate(&text).misspelling_rate;
+    assert!((all - 3.0 * one).abs() < TOLERANCE, "{all} isn't 3 * {one}");
+}
@@ -0,0 +1,41 @@
+//! `model.lm` is decoded at startup, a broken one has to be an error and not a panic on the first
+//! prediction.
+
+use bincode::config::standard;
+use sonai_metrics::NgramModel;
+
+/// Same layout as a stored model, the order and the highest order counts.
+fn stored(order: usize, counts: &[(&str, u32)]) -> Vec<u8> {
+    let counts: Vec<(String, u32)> = counts.iter().map(|&(n, c)| (n.to_string(), c)).collect();
+    bincode::serde::encode_to_vec((order, counts), standard()).unwrap()
+}
+
+fn decode(bytes: &[u8]) -> Result<NgramModel, bincode::error::DecodeError> {
+    bincode::serde::decode_from_slice(bytes, standard()).map(|(model, _)| model)
+}
+
+#[test]
+fn trained_models_round_trip() {
+    let model = NgramModel::train(["the cat sat on the mat"], 3);
+    let bytes = bincode::serde::encode_to_vec(&model, standard()).unwrap();
+    let decoded = decode(&bytes).unwrap();
+
+    assert_eq!(decoded.perplexity("the hat"), model.perplexity("the hat"));
+}
+
+#[test]
+fn empty_models_decode() {
+    assert!(decode(&stored(0, &[])).unwrap().is_empty());
+}
+
+#[test]
+fn order_zero_is_an_error() {
+    let err = decode(&stored(0, &[("a", 1)])).unwrap_err();
+    assert!(err.to_string().contains("invalid n-gram model"), "{err}");
+}
+
+#[test]
+fn ngrams_of_the_wrong_length_are_an_error() {
+    assert!(decode(&stored(3, &[("abc", 2), ("ab", 1)])).is_err());
+    assert!(decode(&stored(2, &[("", 1)])).is_err());
+}
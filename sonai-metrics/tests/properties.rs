@@ -0,0 +1,189 @@
+use proptest::prelude::*;
+use sonai_metrics::{InputFormat, TextMetricFactory, TextMetrics};
+use std::sync::LazyLock;
+
+static FACTORY: LazyLock<TextMetricFactory> = LazyLock::new(|| TextMetricFactory::new().unwrap());
+
+static FORMAT_FACTORIES: LazyLock<Vec<TextMetricFactory>> = LazyLock::new(|| {
+    [InputFormat::Plain, InputFormat::Markdown, InputFormat::Html]
+        .into_iter()
+        .map(|format| TextMetricFactory::new().unwrap().with_input_format(format))
+        .collect()
+});
+
+/// Doesn't contain anything from the phrase lists, and can't complete a phrase that the text
+/// before it started.
+const NEUTRAL: &str = "\n\nCats sleep on warm stones.";
+
+fn assert_finite(metrics: &TextMetrics) {
+    let value = serde_json::to_value(metrics).unwrap();
+
+    for (metric, value) in value.as_object().unwrap() {
+        assert!(
+            value.as_f64().is_some_and(f64::is_finite),
+            "{metric} is {value}"
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn any_text_gives_finite_metrics(text in any::<String>()) {
+        for factory in FORMAT_FACTORIES.iter() {
+            assert_finite(&factory.calculate(&text));
+        }
+    }
+
+    #[test]
+    fn markup_heavy_text_gives_finite_metrics(text in r"[a-z#*_`<>/&;@\-\[\]()|!.:\n 0-9😭🚀—“”]{0,300}") {
+        for factory in FORMAT_FACTORIES.iter() {
+            assert_finite(&factory.calculate(&text));
+        }
+    }
+
+    #[test]
+    fn appending_neutral_text_keeps_counts(text in r"[A-Za-z0-9 ,.!?#:'\n]{0,200}") {
+        let before = FACTORY.calculate(&text);
+        let after = FACTORY.calculate(&format!("{text}{NEUTRAL}"));
+
+        prop_assert_eq!(before.devlog_count, after.devlog_count);
+        prop_assert_eq!(before.not_just_count, after.not_just_count);
+        prop_assert_eq!(before.html_escape_count, after.html_escape_count);
+        prop_assert_eq!(before.backstory_count, after.backstory_count);
+    }
+
+    #[test]
+    fn chunking_doesnt_change_metrics(
+        text in r"[a-z#*`~\-\n .,:\[\]()1😭]{0,300}",
+        splits in prop::collection::vec(any::<prop::sample::Index>(), 0..8),
+    ) {
+        let chars: Vec<char> = text.chars().collect();
+        let mut splits: Vec<usize> = splits.iter().map(|i| i.index(chars.len() + 1)).collect();
+        splits.sort();
+
+        for factory in FORMAT_FACTORIES.iter() {
+            let mut accumulator = factory.accumulator();
+            let mut start = 0;
+
+            for &end in splits.iter().chain([&chars.len()]) {
+                accumulator.push(&chars[start..end].iter().collect::<String>());
+                start = end;
+            }
+
+            prop_assert_eq!(
+                serde_json::to_value(accumulator.finish()).unwrap(),
+                serde_json::to_value(factory.calculate(&text)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn normalization_is_idempotent(text in any::<String>()) {
+        let once = sonai_metrics::normalize(&text);
+        prop_assert_eq!(sonai_metrics::normalize(&once), once);
+    }
+}
+
+#[test]
+fn reference_links_count_like_inline_links() {
+    let inline = FACTORY.calculate("Read [the docs](https://example.com) first.\n\nThen build it.");
+
+    for text in [
+        "Read [the docs][1] first.\n\nThen build it.\n\n[1]: https://example.com",
+        "[1]: https://example.com\n\nRead [the docs][1] first.\n\nThen build it.",
+        "Read [the docs] first.\n\nThen build it.\n\n[The  Docs]: https://example.com",
+    ] {
+        let reference = FACTORY.calculate(text);
+        assert_eq!(reference.link_density, inline.link_density, "{text:?}");
+        assert!(reference.link_density > 0.0);
+
+        let mut accumulator = FACTORY.accumulator();
+        text.split_inclusive(['\n', ' '])
+            .for_each(|chunk| accumulator.push(chunk));
+        assert_eq!(
+            accumulator.finish().link_density,
+            inline.link_density,
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn entities_dont_hide_suspicious_characters() {
+    let html = &FORMAT_FACTORIES[2];
+    let escaped = html.calculate("<p>Pay&#8203;ment&nbsp;to p&#x430;ypal</p>");
+    let literal = html.calculate("<p>Pay\u{200B}ment\u{00A0}to p\u{0430}ypal</p>");
+
+    assert_eq!(escaped.invisible_chars, 1.0);
+    assert_eq!(escaped.unusual_whitespace, 1.0);
+    assert_eq!(escaped.confusables, 1.0);
+    assert_eq!(
+        serde_json::to_value(escaped).unwrap(),
+        serde_json::to_value(literal).unwrap()
+    );
+}
+
+#[test]
+fn unclosed_code_still_counts() {
+    let html = &FORMAT_FACTORIES[2];
+    let closed = html.calculate("<p>run <code>cargo build --release</code></p>");
+    let unclosed = html.calculate("<p>run <code>cargo build --release");
+
+    assert_eq!(unclosed.inline_code, 1.0);
+    assert_eq!(
+        serde_json::to_value(unclosed).unwrap(),
+        serde_json::to_value(closed).unwrap()
+    );
+}
+
+#[test]
+fn inflected_words_arent_misspellings() {
+    let metrics = FACTORY.calculate(
+        "The enemies swept through while I was tweaking the particles. Stopped spawning them, \
+         rebuilt the shaders and it finally worked.",
+    );
+
+    assert_eq!(metrics.misspelling_rate, 0.0);
+}
+
+#[test]
+fn misspelled_inflections_are_misspellings() {
+    for text in [
+        "the enemys",
+        "wait untill then",
+        "teh end",
+        "Teh end",
+        "it recieved",
+    ] {
+        assert!(FACTORY.calculate(text).misspelling_rate > 0.0, "{text}");
+    }
+}
+
+#[test]
+fn dates_stay_in_the_prose() {
+    let undated = FACTORY.calculate("Worked on the parser all evening.");
+
+    for date in ["2024-12-05", "05/12/2024", "05.12.2024"] {
+        let metrics = FACTORY.calculate(&format!("Worked on the parser all evening. {date}"));
+
+        // the -12- /12/ .12. devlog phrases match the month
+        assert_eq!(metrics.devlog_count, undated.devlog_count + 1.0, "{date}");
+        assert_eq!(metrics.inline_code, 0.0, "{date}");
+    }
+}
+
+#[test]
+fn ascii_dashes_and_arrows_arent_irregular() {
+    for text in [
+        "the foo-bar crate",
+        "login -> dashboard",
+        "a well-known bug",
+    ] {
+        let metrics = FACTORY.calculate(text);
+        assert_eq!(metrics.irregular_dashes, 0.0, "{text}");
+        assert_eq!(metrics.irregular_arrows, 0.0, "{text}");
+    }
+
+    assert_eq!(FACTORY.calculate("login → dashboard").irregular_arrows, 1.0);
+    assert_eq!(FACTORY.calculate("fast — really").irregular_dashes, 1.0);
+}
@@ -0,0 +1,97 @@
+//! `TextMetricFactory::version` keys cached metrics, it has to be stable for the same setup and
+//! change with anything that changes the metrics.
+
+use std::fs;
+use std::path::Path;
+
+use sonai_metrics::{InputFormat, LanguageModels, NgramModel, TextMetricFactory};
+
+/// Hash of the golden snapshots and the default version they were computed by. Snapshots that
+/// change mean cached metrics are stale, so the version has to change with them.
+const RECORDED: (&str, &str) = ("02abd8fd4d5a22f0", "4f178508ebf4a26e");
+
+fn factory() -> TextMetricFactory {
+    TextMetricFactory::new().unwrap()
+}
+
+#[test]
+fn same_setup_same_version() {
+    assert_eq!(factory().version(), factory().version());
+    assert_eq!(factory().version().len(), 16);
+}
+
+#[test]
+fn version_changes_with_the_setup() {
+    let version = factory().version();
+
+    assert_ne!(
+        factory().with_input_format(InputFormat::Html).version(),
+        version
+    );
+    assert_ne!(factory().with_emoji_sets(["🚀"], ["😭"]).version(), version);
+}
+
+#[test]
+fn emoji_set_order_doesnt_matter() {
+    assert_eq!(
+        factory().with_emoji_sets(["🚀", "✨"], ["😭"]).version(),
+        factory().with_emoji_sets(["✨", "🚀"], ["😭"]).version()
+    );
+}
+
+#[test]
+fn language_models_are_not_included() {
+    let language_models = LanguageModels {
+        human: NgramModel::train(["some human text"], 3),
+        ai: NgramModel::train(["some ai text"], 3),
+    };
+
+    assert_eq!(
+        factory().with_language_models(language_models).version(),
+        factory().version()
+    );
+}
+
+/// FNV-1a of every `.snap` file in `tests/golden`, in name order
+fn goldens_hash() -> String {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let mut snaps: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "snap")
+        })
+        .collect();
+    snaps.sort();
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for snap in snaps {
+        for byte in fs::read(snap).unwrap() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    format!("{hash:016x}")
+}
+
+#[test]
+fn goldens_dont_change_without_the_version() {
+    let (goldens, version) = RECORDED;
+    let current = (goldens_hash(), factory().version());
+
+    if current.0 != goldens {
+        assert_ne!(
+            current.1, version,
+            "the golden snapshots changed, bump REVISION in src/version.rs"
+        );
+    }
+
+    assert_eq!(
+        (current.0.as_str(), current.1.as_str()),
+        RECORDED,
+        "update RECORDED"
+    );
+}
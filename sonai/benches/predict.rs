@@ -0,0 +1,24 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use sonai::predict;
+use std::hint::black_box;
+
+// the demo predicts on every keystroke, so this is the number that has to stay small
+const DEVLOG: &str = "Spent the evening fighting the borrow checker. Ended up wrapping the state \
+in an Arc<Mutex<State>> and moving the tick loop into its own file.
+
+Bumped tokio and now the release build takes 40s instead of 2 min, no idea why.
+
+also fixed #42 (thanks @sam), the x86-64 build works again";
+
+fn predict_text(c: &mut Criterion) {
+    // the model and factory are loaded lazily, get that out of the way before measuring
+    predict("");
+
+    let mut group = c.benchmark_group("predict");
+    group.bench_function("short", |b| b.iter(|| predict(black_box("Hello, world!"))));
+    group.bench_function("devlog", |b| b.iter(|| predict(black_box(DEVLOG))));
+    group.finish();
+}
+
+criterion_group!(benches, predict_text);
+criterion_main!(benches);
@@ -0,0 +1,10 @@
+// `model.lm` is written by training-bin together with the other model files. Until it has trained
+// the language models there is no file, and the perplexity features stay 0.
+fn main() {
+    println!("cargo::rerun-if-changed=model.lm");
+    println!("cargo::rustc-check-cfg=cfg(language_models)");
+
+    if std::fs::metadata("model.lm").is_ok_and(|model| model.len() > 0) {
+        println!("cargo::rustc-cfg=language_models");
+    }
+}
@@ -5,8 +5,10 @@ use std::sync::LazyLock;
 use linfa_clustering::KMeans;
 use linfa_preprocessing::linear_scaling::LinearScaler;
 use linfa::traits::Transformer;
+use ndarray::s;
 use sonai_metrics::{
-    DistanceFunction, TextMetricFactory, TextMetrics, features_from_metrics, point_confidence,
+    DistanceFunction, LanguageModels, TextMetricFactory, TextMetrics, features_from_metrics,
+    point_confidence,
 };
 
 const AI_CLUSTER: usize =
@@ -33,7 +35,28 @@ static SCALER: LazyLock<LinearScaler<f64>> = LazyLock::new(|| {
     .0
 });
 
-static METRICS: LazyLock<TextMetricFactory> = LazyLock::new(|| TextMetricFactory::new().unwrap());
+#[cfg(language_models)]
+fn language_models() -> LanguageModels {
+    let config = bincode::config::standard();
+    bincode::serde::decode_from_slice(
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/model.lm")),
+        config,
+    )
+    .unwrap()
+    .0
+}
+
+// not trained yet, see build.rs
+#[cfg(not(language_models))]
+fn language_models() -> LanguageModels {
+    LanguageModels::default()
+}
+
+static METRICS: LazyLock<TextMetricFactory> = LazyLock::new(|| {
+    TextMetricFactory::new()
+        .unwrap()
+        .with_language_models(language_models())
+});
 
 #[derive(Debug, serde::Serialize)]
 pub struct Prediction {
@@ -48,6 +71,9 @@ fn _predict(devlog: &str) -> Prediction {
 
     let features = features_from_metrics(&[&sample]); // Array2<f64> of shape (1, n_features)
 
+    // feature columns are append-only, only feed the model the ones it was trained on
+    let features = features.slice_move(s![.., ..SCALER.offsets().len()]);
+
     let scaled_features = SCALER.transform(features); // still (1, n_features)
 
     let features_row = scaled_features.row(0);
@@ -0,0 +1,53 @@
+//! Time budget for `predict`, the demo runs it on every keystroke. `benches/predict.rs` has the
+//! numbers, this only fails when a change makes it several times slower. Timings depend on the
+//! machine, so it's ignored by default and meant for release builds on a quiet one:
+//!
+//! ```sh
+//! cargo test --release -p sonai --test budget -- --ignored
+//! ```
+
+use std::time::{Duration, Instant};
+
+use sonai::predict;
+
+const DEVLOG: &str = "Spent the evening fighting the borrow checker. Ended up wrapping the state \
+in an Arc<Mutex<State>> and moving the tick loop into its own file.
+
+Bumped tokio and now the release build takes 40s instead of 2 min, no idea why.
+
+also fixed #42 (thanks @sam), the x86-64 build works again";
+
+/// Fastest of a few runs, a busy machine only ever makes a run slower
+fn fastest(text: &str) -> Duration {
+    if cfg!(debug_assertions) {
+        panic!("the budget is for release builds, run with --release");
+    }
+
+    // the model and factory are loaded lazily, that's not what's measured
+    predict("");
+
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(predict(std::hint::black_box(text)));
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+#[test]
+#[ignore = "timing, run in release with --ignored"]
+fn devlog_within_budget() {
+    let elapsed = fastest(DEVLOG);
+
+    assert!(elapsed < Duration::from_millis(2), "took {elapsed:?}");
+}
+
+#[test]
+#[ignore = "timing, run in release with --ignored"]
+fn long_text_within_budget() {
+    let elapsed = fastest(&[DEVLOG; 20].join("\n\n"));
+
+    assert!(elapsed < Duration::from_millis(20), "took {elapsed:?}");
+}
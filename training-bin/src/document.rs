@@ -0,0 +1,160 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::bail;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// Which data set a document came from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Corpus {
+    Flavortown,
+    Som,
+    /// Known AI text from `ai.data`
+    Ai,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    Project,
+    Devlog,
+}
+
+/// Ground truth, for the texts where someone actually knows
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Label {
+    Human,
+    Ai,
+}
+
+/// A text and everything the source told us about it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Document {
+    /// Unique within the corpus, `devlog/123`
+    pub id: String,
+    pub source: Corpus,
+    pub kind: Option<Kind>,
+    pub author: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+    /// Id of the project, the one a devlog belongs to or the project itself
+    pub project: Option<String>,
+    /// Time logged on a devlog
+    pub duration_seconds: Option<u64>,
+    pub text: String,
+    pub label: Option<Label>,
+}
+
+impl Document {
+    /// Texts from the `Vec<String>` data files, the position is all that identifies them
+    pub fn from_texts(texts: Vec<String>, source: Corpus, label: Option<Label>) -> Vec<Self> {
+        texts
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| Self {
+                id: format!("{source}/{i}"),
+                source,
+                kind: None,
+                author: None,
+                created_at: None,
+                project: None,
+                duration_seconds: None,
+                text,
+                label,
+            })
+            .collect()
+    }
+}
+
+/// Timestamps from APIs, a timestamp that doesn't parse is treated as missing
+pub fn parse_timestamp(timestamp: Option<&str>) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(timestamp?, &Rfc3339).ok()
+}
+
+impl fmt::Display for Corpus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Flavortown => "flavortown",
+            Self::Som => "som",
+            Self::Ai => "ai",
+        })
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Project => "project",
+            Self::Devlog => "devlog",
+        })
+    }
+}
+
+impl fmt::Display for Document {
+    /// One line summary for the cluster samples, `flavortown devlog/12 by 3 on 2025-12-01`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.source, self.id)?;
+
+        if let Some(author) = &self.author {
+            write!(f, " by {author}")?;
+        }
+
+        if let Some(created_at) = self.created_at {
+            write!(f, " on {}", created_at.date())?;
+        }
+
+        if let Some(project) = &self.project {
+            write!(f, " in project/{project}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Human => "human",
+            Self::Ai => "ai",
+        })
+    }
+}
+
+impl FromStr for Corpus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            "flavortown" => Self::Flavortown,
+            "som" => Self::Som,
+            "ai" => Self::Ai,
+            _ => bail!("unknown corpus {s}"),
+        })
+    }
+}
+
+impl FromStr for Kind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            "project" => Self::Project,
+            "devlog" => Self::Devlog,
+            _ => bail!("unknown kind {s}"),
+        })
+    }
+}
+
+impl FromStr for Label {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            "human" => Self::Human,
+            "ai" => Self::Ai,
+            _ => bail!("unknown label {s}"),
+        })
+    }
+}
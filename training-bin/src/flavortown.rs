@@ -1,14 +1,29 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
 
 use crate::{
-    flavortown::sources::{Devlogs, Projects},
-    network::concurrent_pagintated_retry_fetch,
+    document::{Corpus, Document, Kind, parse_timestamp},
+    flavortown::sources::{Devlogs, Project, Projects},
+    network::{Limits, PageCache, concurrent_pagintated_retry_fetch},
 };
 
-mod sources;
+pub mod sources;
+
+pub const API: &str = "https://flavortown.hackclub.com/api/v1";
+
+pub async fn fetch_all(api_key: &str, cache: &PageCache, limits: &Limits) -> Result<Vec<Document>> {
+    fetch_all_from(API, api_key, cache, limits).await
+}
 
-pub async fn fetch_all(api_key: &str) -> Result<Vec<String>> {
+/// `fetch_all` against another server, the tests use a local mock of the API
+pub async fn fetch_all_from(
+    api: &str,
+    api_key: &str,
+    cache: &PageCache,
+    limits: &Limits,
+) -> Result<Vec<Document>> {
     let mut headers = HeaderMap::new();
     headers.insert(
         AUTHORIZATION,
@@ -19,27 +34,65 @@ pub async fn fetch_all(api_key: &str) -> Result<Vec<String>> {
         .default_headers(headers)
         .build()?;
 
-    let projects = concurrent_pagintated_retry_fetch::<Projects>(&client).await?;
+    let projects =
+        concurrent_pagintated_retry_fetch(&client, &Projects::new(api), cache, limits).await?;
+
+    let devlogs =
+        concurrent_pagintated_retry_fetch(&client, &Devlogs::new(api), cache, limits).await?;
 
-    let devlogs = concurrent_pagintated_retry_fetch::<Devlogs>(&client).await?;
+    // devlogs don't always say which project they're in, projects list their devlogs
+    let parents: HashMap<u64, &Project> = projects
+        .iter()
+        .flat_map(|project| project.devlog_ids.iter().map(move |&id| (id, project)))
+        .collect();
 
-    Ok(projects.into_iter().filter_map(|project| {
-        let desc = project.description.replace("This is my first project on Flavortown.", "").replace("Im excited to share my progress!", "").replace("I'm excited to share my progress!", "");
-        let desc = desc.trim();
+    let projects = projects.iter().filter_map(|project| {
+        // default descriptions are kept, sonai_metrics counts them as boilerplate
+        let desc = project.description.trim();
 
         if !desc.is_empty() {
-            Some(desc.to_string())
+            Some(Document {
+                id: format!("project/{}", project.id),
+                source: Corpus::Flavortown,
+                kind: Some(Kind::Project),
+                author: project.user_id.map(|id| id.to_string()),
+                created_at: parse_timestamp(project.created_at.as_deref()),
+                project: Some(project.id.to_string()),
+                duration_seconds: None,
+                text: desc.to_string(),
+                label: None,
+            })
         } else {
             None
         }
-    }).chain(devlogs.into_iter().filter_map(|devlog| {
-            let body = devlog.body.replace("I'm working on my first project! This is so exciting. I can't wait to share more updates as I build.", "");
-            let body = body.trim();
-
-            if !body.is_empty() {
-                Some(body.to_string())
-            } else {
-                None
-            }
-        })).collect())
+    });
+
+    let devlogs = devlogs.iter().filter_map(|devlog| {
+        let body = devlog.body.trim();
+        let parent = parents.get(&devlog.id);
+
+        if !body.is_empty() {
+            Some(Document {
+                id: format!("devlog/{}", devlog.id),
+                source: Corpus::Flavortown,
+                kind: Some(Kind::Devlog),
+                author: devlog
+                    .user_id
+                    .or(parent.and_then(|project| project.user_id))
+                    .map(|id| id.to_string()),
+                created_at: parse_timestamp(devlog.created_at.as_deref()),
+                project: devlog
+                    .project_id
+                    .or(parent.map(|project| project.id))
+                    .map(|id| id.to_string()),
+                duration_seconds: devlog.duration_seconds,
+                text: body.to_string(),
+                label: None,
+            })
+        } else {
+            None
+        }
+    });
+
+    Ok(projects.chain(devlogs).collect())
 }
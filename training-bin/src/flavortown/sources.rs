@@ -1,51 +1,135 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::network::{Pagination, Pagintated};
+use crate::network::{Order, PageMeta, PageNumber, Pagination, PaginationStrategy, Source};
 
 #[derive(Deserialize, Clone)]
-pub struct Devlogs {
+pub struct DevlogsPage {
     devlogs: Vec<Devlog>,
     pagination: Pagination,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Devlog {
+    pub id: u64,
     pub body: String,
+    #[serde(default)]
+    pub project_id: Option<u64>,
+    #[serde(default)]
+    pub user_id: Option<u64>,
+    #[serde(default)]
+    pub duration_seconds: Option<u64>,
+    #[serde(default)]
+    pub created_at: Option<String>,
 }
 
 #[derive(Deserialize, Clone)]
-pub struct Projects {
+pub struct ProjectsPage {
     projects: Vec<Project>,
     pagination: Pagination,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Project {
+    pub id: u64,
+    #[serde(default)]
+    pub title: String,
     pub description: String,
+    #[serde(default)]
+    pub user_id: Option<u64>,
+    #[serde(default)]
+    pub repo_url: Option<String>,
+    #[serde(default)]
+    pub demo_url: Option<String>,
+    #[serde(default)]
+    pub devlog_ids: Vec<u64>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+pub struct Devlogs {
+    url: String,
+}
+
+impl Devlogs {
+    pub fn new(api: &str) -> Self {
+        Self {
+            url: format!("{api}/devlogs"),
+        }
+    }
+}
+
+pub struct Projects {
+    url: String,
+}
+
+impl Projects {
+    pub fn new(api: &str) -> Self {
+        Self {
+            url: format!("{api}/projects"),
+        }
+    }
 }
 
-impl Pagintated for Devlogs {
-    const ROOT: &str = "https://flavortown.hackclub.com/api/v1/devlogs";
+impl Source for Devlogs {
+    type Page = DevlogsPage;
     type Data = Devlog;
 
-    fn page(self) -> Vec<Devlog> {
-        self.devlogs
+    fn url(&self) -> &str {
+        &self.url
     }
 
-    fn pagination(&self) -> &Pagination {
-        &self.pagination
+    fn pagination(&self) -> impl PaginationStrategy {
+        PageNumber::default()
+    }
+
+    // the API docs don't give an order and no sort parameter is sent. Assumed oldest first, new
+    // devlogs on the last page, an incremental fetch checks it and fetches everything again when
+    // the first page changed
+    fn order(&self) -> Order {
+        Order::OldestFirst
+    }
+
+    fn items(page: DevlogsPage) -> Vec<Devlog> {
+        page.devlogs
+    }
+
+    fn id(devlog: &Devlog) -> String {
+        devlog.id.to_string()
+    }
+
+    fn meta(page: &DevlogsPage) -> PageMeta {
+        page.pagination.meta()
     }
 }
 
-impl Pagintated for Projects {
-    const ROOT: &str = "https://flavortown.hackclub.com/api/v1/projects";
+impl Source for Projects {
+    type Page = ProjectsPage;
     type Data = Project;
 
-    fn page(self) -> Vec<Project> {
-        self.projects
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn pagination(&self) -> impl PaginationStrategy {
+        PageNumber::default()
+    }
+
+    // the API docs don't give an order and no sort parameter is sent. Assumed oldest first, new
+    // projects on the last page, an incremental fetch checks it and fetches everything again when
+    // the first page changed
+    fn order(&self) -> Order {
+        Order::OldestFirst
+    }
+
+    fn items(page: ProjectsPage) -> Vec<Project> {
+        page.projects
+    }
+
+    fn id(project: &Project) -> String {
+        project.id.to_string()
     }
 
-    fn pagination(&self) -> &Pagination {
-        &self.pagination
+    fn meta(page: &ProjectsPage) -> PageMeta {
+        page.pagination.meta()
     }
 }
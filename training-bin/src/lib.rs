@@ -0,0 +1,14 @@
+//! Data fetching for the training binary, generic over paginated APIs so more sources can be
+//! added next to Flavortown. Everything fetched ends up as a [`document::Document`],
+//! [`report`] turns the predictions on them into AI share over time and [`review`] into a queue
+//! of authors or projects for moderators. All of it is kept in the [`store`], along with the
+//! [`metrics`] computed for every text.
+
+pub mod document;
+pub mod flavortown;
+pub mod logging;
+pub mod metrics;
+pub mod network;
+pub mod report;
+pub mod review;
+pub mod store;
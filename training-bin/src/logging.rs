@@ -0,0 +1,62 @@
+//! Logs for the training binary. Readable lines and progress bars in a terminal, or one JSON
+//! object per line for the scheduled training jobs. Either way they go to stderr, stdout is left
+//! for the cluster samples and the review queue.
+
+use indicatif::ProgressStyle;
+use tracing::Span;
+use tracing::level_filters::LevelFilter;
+use tracing_indicatif::IndicatifLayer;
+use tracing_indicatif::span_ext::IndicatifSpanExt;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, fmt};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Pretty,
+    Json,
+}
+
+/// Logs `level` and up from this crate and warnings from everything else. `RUST_LOG` replaces
+/// that when it's set.
+pub fn init(level: LevelFilter, format: Format) {
+    let filter = match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Ok(directives) => EnvFilter::new(directives),
+        Err(_) => EnvFilter::new(format!("warn,training={level}")),
+    };
+
+    let registry = tracing_subscriber::registry().with(filter);
+
+    match format {
+        Format::Pretty => {
+            // bars are drawn by the layer, logs have to go through it so they don't tear them
+            let indicatif = IndicatifLayer::new();
+
+            registry
+                .with(fmt::layer().with_writer(indicatif.get_stderr_writer()))
+                .with(indicatif)
+                .init();
+        }
+        Format::Json => registry
+            .with(fmt::layer().json().with_writer(std::io::stderr))
+            .init(),
+    }
+}
+
+/// Turns `span` into a progress bar, a spinner with a count when `len` isn't known yet. Does
+/// nothing without the pretty logs.
+pub fn progress(span: &Span, len: Option<u64>) {
+    let template = match len {
+        Some(_) => "{span_child_prefix}{spinner} {span_name} [{bar:40}] {pos}/{len} {span_fields}",
+        None => "{span_child_prefix}{spinner} {span_name} {pos} {span_fields}",
+    };
+
+    span.pb_set_style(
+        &ProgressStyle::with_template(template)
+            .expect("valid template")
+            .progress_chars("=> "),
+    );
+
+    if let Some(len) = len {
+        span.pb_set_length(len);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use bincode::config::standard;
 
@@ -8,87 +9,175 @@ use linfa::Dataset;
 use linfa::traits::{Fit, Predict, Transformer};
 use linfa_clustering::KMeans;
 use linfa_preprocessing::linear_scaling::LinearScaler;
-use ndarray::{Array1, Array2};
+use ndarray::{Array1, Array2, Axis};
 use num_format::{Locale, ToFormattedString};
 use rand::seq::IndexedRandom;
 use rand_xoshiro::Xoshiro256PlusPlus;
 use rand_xoshiro::rand_core::SeedableRng;
 use time::{OffsetDateTime, format_description};
 use tokio::fs;
+use tracing::level_filters::LevelFilter;
+use tracing::{info, warn};
+
+use sonai_metrics::{
+    DIST_FN, DistanceFunction, LANGUAGE_MODEL_FEATURES, N_FEATURES, features_from_metrics,
+    point_confidence,
+};
+use sonai_metrics::{LanguageModels, NgramModel, TextMetricFactory, TextMetrics};
+use training::document::{Corpus, Document, Label};
+use training::flavortown::fetch_all;
+use training::logging::{self, Format};
+use training::metrics::{calculate, metrics_version};
+use training::network::{FetchMode, Limits, PageCache};
+use training::report::{Bucket, TimeSeries};
+use training::review::{GroupBy, Prediction, csv, review_queue};
+use training::store::Store;
+
+/// Character n-gram order of the language models, higher orders get big fast
+const NGRAM_ORDER: usize = 3;
+
+/// Every document, fetch run and label
+const STORE: &str = "corpus.db";
+
+/// Where the corpora were kept before the store, and what's known about their texts
+const DATA_FILES: [(&str, Corpus, Option<Label>); 3] = [
+    ("ftwn.data", Corpus::Flavortown, None),
+    ("som.data", Corpus::Som, None),
+    ("ai.data", Corpus::Ai, Some(Label::Ai)),
+];
+
+fn cluster(
+    features: Array2<f64>,
+) -> anyhow::Result<(LinearScaler<f64>, KMeans<f64, DistanceFunction>)> {
+    let n_samples = features.nrows();
+    let dataset = Dataset::new(features, Array2::<f32>::zeros((n_samples, 0)));
 
-mod flavortown;
-mod network;
-
-use crate::flavortown::fetch_all;
-use sonai_metrics::{DIST_FN, DistanceFunction, features_from_metrics};
-use sonai_metrics::{TextMetricFactory, TextMetrics};
+    let scaler = LinearScaler::standard().fit(&dataset)?;
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let config = standard();
+    let dataset = scaler.transform(dataset);
 
-    println!("Fetching projects + devlogs");
+    let rng = Xoshiro256PlusPlus::seed_from_u64(0xAB17349264ABCABA);
 
-    let ftwn_data: Vec<String> = if fs::try_exists("ftwn.data").await? {
-        let data = fs::read("ftwn.data").await?;
-        let result: Vec<String> = decode_from_slice(&data, config)?.0;
+    let model: KMeans<f64, DistanceFunction> = KMeans::params_with(2, rng, DIST_FN)
+        .max_n_iterations(1000)
+        .n_runs(10)
+        .fit(&dataset)?;
 
-        result
-    } else {
-        let env_map = dotenvy::EnvLoader::new().load()?;
-        let logs = fetch_all(&env_map.var("FLAVORTOWN_API_KEY")?).await?;
+    Ok((scaler, model))
+}
 
-        fs::write("ftwn.data", encode_to_vec(&logs, config)?).await?;
+/// `--resume` picks up an interrupted fetch, `--incremental` adds what's new since the last one
+/// and `--fresh` starts over. Without a flag the store is used when it has flavortown documents.
+fn fetch_mode() -> Option<FetchMode> {
+    std::env::args().skip(1).find_map(|arg| match arg.as_str() {
+        "--fresh" => Some(FetchMode::Fresh),
+        "--resume" => Some(FetchMode::Resume),
+        "--incremental" => Some(FetchMode::Incremental),
+        _ => None,
+    })
+}
 
-        logs
-    };
+/// `-v` for debug logs, `-vv` for every request, `-q` for only warnings and `--log-json` for
+/// JSON lines instead of progress bars
+fn log_options() -> (LevelFilter, Format) {
+    let mut level = LevelFilter::INFO;
+    let mut format = Format::Pretty;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "-v" => level = LevelFilter::DEBUG,
+            "-vv" => level = LevelFilter::TRACE,
+            "-q" => level = LevelFilter::WARN,
+            "--log-json" => format = Format::Json,
+            _ => {}
+        }
+    }
 
-    let som_data: Vec<String> = if fs::try_exists("som.data").await? {
-        let data = fs::read("som.data").await?;
-        let result: Vec<String> = decode_from_slice(&data, config)?.0;
+    (level, format)
+}
 
-        result
-    } else {
-        vec![]
+/// `--review author` or `--review project` ranks authors or projects by how much of what they
+/// posted is flagged, `--min-posts 5` leaves out anyone with fewer posts than that (default 3)
+fn review() -> anyhow::Result<Option<(GroupBy, usize)>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let value = |flag: &str| args.iter().skip_while(|arg| *arg != flag).nth(1);
+
+    let group_by = match value("--review").map(String::as_str) {
+        Some("author") => GroupBy::Author,
+        Some("project") => GroupBy::Project,
+        Some(other) => anyhow::bail!("--review takes author or project, not {other}"),
+        None => return Ok(None),
     };
+    let min_posts = value("--min-posts")
+        .map(|n| n.parse())
+        .transpose()?
+        .unwrap_or(3);
 
-    let mut data = ftwn_data.clone();
-    data.extend(som_data.into_iter());
-
-    println!("Calculating metrics");
-    let metrics: Vec<TextMetrics> = TextMetricFactory::new()?.calculate_iter(&data).collect();
-    let metrics_refs: Vec<&TextMetrics> = metrics.iter().collect();
-    let features = features_from_metrics(&metrics_refs);
-
-    println!("Building dataset");
-    let dataset = Dataset::new(features.clone(), Array2::<f32>::zeros((metrics.len(), 0)));
+    Ok(Some((group_by, min_posts)))
+}
 
-    let scaler = LinearScaler::standard().fit(&dataset)?;
+/// `--export` writes the corpora in the store back out as `.data` files
+fn export() -> bool {
+    std::env::args().any(|arg| arg == "--export")
+}
 
-    let dataset = scaler.transform(dataset);
+/// Fills the store with the `.data` files of corpora it doesn't have yet, and `ftwn.documents`
+/// from before the store
+async fn import_data_files(store: &mut Store) -> anyhow::Result<()> {
+    if store.count(Corpus::Flavortown)? == 0 && fs::try_exists("ftwn.documents").await? {
+        let data = fs::read("ftwn.documents").await?;
+        let documents: Vec<Document> = decode_from_slice(&data, standard())?.0;
+
+        store.insert_documents(&documents)?;
+        info!(
+            path = "ftwn.documents",
+            imported = documents.len(),
+            "imported"
+        );
+    }
 
-    let rng = Xoshiro256PlusPlus::seed_from_u64(0xAB17349264ABCABA);
+    for (path, corpus, label) in DATA_FILES {
+        if store.count(corpus)? == 0 && Path::new(path).exists() {
+            let imported = store.import(path, corpus, label)?;
+            info!(path, imported, "imported");
+        }
+    }
 
-    let model: KMeans<f64, DistanceFunction> = KMeans::params_with(2, rng, DIST_FN)
-        .max_n_iterations(1000)
-        .n_runs(10)
-        .fit(&dataset)?;
+    Ok(())
+}
 
-    fs::write("../sonai/model.scaler", encode_to_vec(&scaler, config)?).await?;
-    fs::write("../sonai/model.kmeans", encode_to_vec(&model, config)?).await?;
+/// Flavortown from the store, fetched into it first with a fetch mode or when there's nothing yet
+async fn flavortown_documents(
+    store: &mut Store,
+    fetch_mode: Option<FetchMode>,
+) -> anyhow::Result<Vec<Document>> {
+    if fetch_mode.is_none() && store.count(Corpus::Flavortown)? > 0 {
+        return store.current(Corpus::Flavortown);
+    }
 
-    println!("Predicting");
-    let predicted: Array1<usize> = {
-        let metrics: Vec<TextMetrics> = TextMetricFactory::new()?
-            .calculate_iter(&ftwn_data)
-            .collect();
-        let metrics_refs: Vec<&TextMetrics> = metrics.iter().collect();
-        let features = features_from_metrics(&metrics_refs);
-        let features = scaler.transform(features);
+    let env_map = dotenvy::EnvLoader::new().load()?;
+    // pages are cached as they arrive, so a crashed fetch can be resumed
+    let cache = PageCache::new("cache", fetch_mode.unwrap_or(FetchMode::Resume));
+    let started_at = OffsetDateTime::now_utc();
+    let documents = fetch_all(
+        &env_map.var("FLAVORTOWN_API_KEY")?,
+        &cache,
+        &Limits::default(),
+    )
+    .await?;
+
+    let run = store.record_run(Corpus::Flavortown, started_at, &documents)?;
+    info!(run, documents = documents.len(), "fetch stored");
+
+    Ok(documents)
+}
 
-        model.predict(&features)
-    };
+fn texts(documents: &[Document]) -> impl Iterator<Item = &str> {
+    documents.iter().map(|document| document.text.as_str())
+}
 
+/// The cluster with the most emojis is the AI one
+fn ai_cluster(metrics: &[TextMetrics], predicted: &Array1<usize>) -> usize {
     let (emoji_sums, counts) = metrics.iter().zip(predicted.iter()).fold(
         ([0.0f64; 2], [0usize; 2]),
         |(mut current_emoji_sums, mut current_counts), (metric, &label)| {
@@ -103,24 +192,176 @@ async fn main() -> anyhow::Result<()> {
         emoji_sums[1] / (counts[1].max(1) as f64),
     ];
 
-    let ai_label = if avg_emoji[0] > avg_emoji[1] { 0 } else { 1 };
-    let human_label = if avg_emoji[0] > avg_emoji[1] { 1 } else { 0 };
+    if avg_emoji[0] > avg_emoji[1] { 0 } else { 1 }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let config = standard();
+    let (level, format) = log_options();
+    logging::init(level, format);
+    let review = review()?;
+
+    let mut store = Store::open(STORE)?;
+    import_data_files(&mut store).await?;
+
+    if export() {
+        for (path, corpus, _) in DATA_FILES {
+            if store.count(corpus)? == 0 {
+                continue;
+            }
+
+            let exported = store.export(corpus, path)?;
+            info!(path, exported, "exported");
+        }
+
+        return Ok(());
+    }
+
+    info!("Fetching projects + devlogs");
+
+    let ftwn_data = flavortown_documents(&mut store, fetch_mode()).await?;
+    let som_data = store.current(Corpus::Som)?;
+    let ai_data = store.current(Corpus::Ai)?;
+
+    let mut data = ftwn_data;
+    data.extend(som_data.into_iter());
+
+    info!(documents = data.len(), "Calculating metrics");
+    let factory = TextMetricFactory::new()?;
+    let base_version = metrics_version(&factory, None);
+    let metrics = calculate(&factory, &mut store, &base_version, &data)?;
+    let metrics_refs: Vec<&TextMetrics> = metrics.iter().collect();
+    let features = features_from_metrics(&metrics_refs);
+
+    // The language models are trained on the clusters, so cluster once without them first
+    info!("Clustering without language models");
+    let base_columns: Vec<usize> = (0..N_FEATURES)
+        .filter(|i| !LANGUAGE_MODEL_FEATURES.contains(i))
+        .collect();
+    let (scaler, model) = cluster(features.select(Axis(1), &base_columns))?;
+    let predicted = model.predict(&scaler.transform(features.select(Axis(1), &base_columns)));
+    let ai_label = ai_cluster(&metrics, &predicted);
+
+    info!("Training language models");
+    let human_texts = data
+        .iter()
+        .zip(predicted.iter())
+        .filter(|&(_, &label)| label != ai_label)
+        .map(|(document, _)| &document.text);
+
+    // Prefer known AI text, fall back to whatever the first clustering thinks is AI
+    let ai_model = if ai_data.is_empty() {
+        let ai_texts = data
+            .iter()
+            .zip(predicted.iter())
+            .filter(|&(_, &label)| label == ai_label)
+            .map(|(document, _)| &document.text);
+
+        NgramModel::train(ai_texts, NGRAM_ORDER)
+    } else {
+        NgramModel::train(texts(&ai_data), NGRAM_ORDER)
+    };
+
+    let language_models = LanguageModels {
+        human: NgramModel::train(human_texts, NGRAM_ORDER),
+        ai: ai_model,
+    };
+
+    // sonai picks up any model.lm, an empty one would ship perplexity features that are always 0
+    anyhow::ensure!(
+        !language_models.human.is_empty() && !language_models.ai.is_empty(),
+        "no human or no AI text to train the language models on"
+    );
+
+    let language_models_file = encode_to_vec(&language_models, config)?;
+    fs::write("../sonai/model.lm", &language_models_file).await?;
+
+    let factory = factory.with_language_models(language_models);
+    let version = metrics_version(&factory, Some(&language_models_file));
+
+    info!("Recalculating metrics");
+    let metrics = calculate(&factory, &mut store, &version, &data)?;
+    store.retain_metrics(&[&base_version, &version])?;
+    let metrics_refs: Vec<&TextMetrics> = metrics.iter().collect();
+    let features = features_from_metrics(&metrics_refs);
+
+    info!("Building dataset");
+    let (scaler, model) = cluster(features.clone())?;
+
+    fs::write("../sonai/model.scaler", encode_to_vec(&scaler, config)?).await?;
+    fs::write("../sonai/model.kmeans", encode_to_vec(&model, config)?).await?;
+
+    info!("Predicting");
+    let features = scaler.transform(features);
+    let predicted: Array1<usize> = model.predict(&features);
+
+    let ai_label = ai_cluster(&metrics, &predicted);
+    let human_label = 1 - ai_label;
 
     fs::write("../sonai/model.ai.cluster", [ai_label as u8]).await?;
 
-    let cluster_counts = predicted.iter().fold([0; 2], |mut counts, &label| {
-        counts[label] += 1;
-        counts
-    });
+    // the headline numbers are flavortown only, som is part of the time series
+    let cluster_counts = predicted
+        .iter()
+        .zip(&data)
+        .filter(|(_, document)| document.source == Corpus::Flavortown)
+        .fold([0; 2], |mut counts, (&label, _)| {
+            counts[label] += 1;
+            counts
+        });
 
     let ai = cluster_counts[ai_label];
     let human = cluster_counts[human_label];
     let total = ai + human;
 
-    let mut clusters: HashMap<usize, Vec<(TextMetrics, String)>> = HashMap::new();
+    let predictions = || {
+        data.iter()
+            .zip(predicted.iter().map(|&label| label == ai_label))
+    };
+    let daily = TimeSeries::new(Bucket::Day, predictions());
+    let weekly = TimeSeries::new(Bucket::Week, predictions());
 
-    for ((label, metrics), devlog) in predicted.into_iter().zip(metrics).zip(data) {
-        clusters.entry(label).or_default().push((metrics, devlog));
+    if weekly.undated > 0 {
+        warn!(
+            undated = weekly.undated,
+            "documents without a timestamp left out of the time series"
+        );
+    }
+
+    fs::write("ai_share.day.csv", daily.csv()).await?;
+    fs::write("ai_share.week.csv", weekly.csv()).await?;
+
+    if let Some((group_by, min_posts)) = review {
+        let predictions =
+            data.iter()
+                .zip(features.rows())
+                .zip(&predicted)
+                .map(|((document, row), &label)| Prediction {
+                    document,
+                    probability: point_confidence(&model, row).1[ai_label],
+                    flagged: label == ai_label,
+                });
+        let queue = review_queue(group_by, predictions, min_posts);
+
+        fs::write(format!("review.{group_by}.csv"), csv(group_by, &queue)).await?;
+
+        println!(
+            "\n{}",
+            format!("==================== Review queue ({group_by}) ====================")
+                .bold()
+                .cyan()
+        );
+
+        for group in queue.iter().take(10) {
+            println!("{group}");
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<(TextMetrics, Document)>> = HashMap::new();
+
+    for ((label, metrics), document) in predicted.into_iter().zip(metrics).zip(data) {
+        clusters.entry(label).or_default().push((metrics, document));
     }
 
     let mut rng = rand::rng();
@@ -138,10 +379,11 @@ async fn main() -> anyhow::Result<()> {
 
         let sample = items.choose_multiple(&mut rng, 5);
 
-        for (i, (metrics, devlog)) in sample.into_iter().enumerate() {
+        for (i, (metrics, document)) in sample.into_iter().enumerate() {
             println!("{}", format!("--- Sample {i} ---").bold().yellow());
+            println!("{} {}", "Document:".magenta(), document);
             println!("{} {}", "Features:".green(), metrics);
-            println!("{}\n{}", "Text:".blue(), devlog);
+            println!("{}\n{}", "Text:".blue(), document.text);
             println!("{}", "-------------------------------\n".dimmed());
         }
     }
@@ -149,7 +391,10 @@ async fn main() -> anyhow::Result<()> {
     let human_pct = (human as f64) * 100. / (total as f64);
     let ai_pct = (ai as f64) * 100. / (total as f64);
 
-    println!("ai_cluster={ai_label} human=({human_pct:.2}%, {human}) ai=({ai_pct:.2}%, {ai})",);
+    info!(
+        ai_cluster = ai_label,
+        human, ai, "human={human_pct:.2}% ai={ai_pct:.2}%"
+    );
 
     let human = human.to_formatted_string(&Locale::en);
     let ai = ai.to_formatted_string(&Locale::en);
@@ -161,6 +406,9 @@ async fn main() -> anyhow::Result<()> {
         )
         .expect("today is a day");
 
+    let chart = weekly.svg();
+    let legend = weekly.legend();
+
     let file = format!(
         r##"<!-- Do not change this file manually, please edit the template string at the bottom of training-bin/src/main.rs and rebuild  -->
 <!doctype html>
@@ -202,10 +450,11 @@ async fn main() -> anyhow::Result<()> {
             <span class="font-semibold">Human %:</span>
             <span class="text-green-600 dark:text-green-400">{human_pct:.2}%</span>
           </div>
-          <div class="flex items-center space-x-2">
-            <span class="font-semibold">AI %:</span>
-            <span class="text-blue-600 dark:text-blue-400">{ai_pct:.2}%</span>
-          </div>
+        </div>
+        <h3 class="text-xl font-medium mt-6 mb-2">AI % per week</h3>
+        {chart}
+        <div class="flex flex-wrap gap-4 text-sm">
+          {legend}
         </div>
       </section>
 
@@ -0,0 +1,71 @@
+//! Metrics of documents, cached in the [`Store`] by text hash and metrics version so only new and
+//! edited documents are computed. A new version (lists, feature layout or language models
+//! changed) misses the cache for everything.
+
+use anyhow::Result;
+use sonai_metrics::{TextMetricFactory, TextMetrics};
+use tracing::{info, info_span};
+use tracing_indicatif::span_ext::IndicatifSpanExt;
+
+use crate::document::Document;
+use crate::logging::progress;
+use crate::store::{Store, text_hash};
+
+/// What the metrics of `factory` depend on, `language_models` are the encoded models it was given
+/// (the `model.lm` file)
+pub fn metrics_version(factory: &TextMetricFactory, language_models: Option<&[u8]>) -> String {
+    match language_models {
+        Some(language_models) => format!(
+            "{}-{}",
+            factory.version(),
+            &blake3::hash(language_models).to_hex()[..16]
+        ),
+        None => factory.version(),
+    }
+}
+
+/// Metrics of every document, from the store when they were computed by this `version` before.
+/// The ones that weren't are stored, with a progress bar over all of them.
+pub fn calculate(
+    factory: &TextMetricFactory,
+    store: &mut Store,
+    version: &str,
+    documents: &[Document],
+) -> Result<Vec<TextMetrics>> {
+    let span = info_span!("metrics", version);
+    progress(&span, Some(documents.len() as u64));
+    let _entered = span.enter();
+
+    let mut metrics = Vec::with_capacity(documents.len());
+    // (index in metrics, text hash) of the ones that had to be computed
+    let mut computed = Vec::new();
+
+    for document in documents {
+        let hash = text_hash(&document.text);
+
+        match store.metrics(&hash, version)? {
+            Some(cached) => metrics.push(cached),
+            None => {
+                computed.push((metrics.len(), hash));
+                metrics.push(factory.calculate(&document.text));
+            }
+        }
+
+        span.pb_inc(1);
+    }
+
+    store.store_metrics(
+        version,
+        computed
+            .iter()
+            .map(|(index, hash)| (hash.as_str(), &metrics[*index])),
+    )?;
+
+    info!(
+        cached = documents.len() - computed.len(),
+        computed = computed.len(),
+        "metrics ready"
+    );
+
+    Ok(metrics)
+}
@@ -1,253 +1,685 @@
-use anyhow::{Result, anyhow};
-use reqwest::header::RETRY_AFTER;
-use reqwest::{Client, StatusCode};
-use serde::Deserialize;
+use anyhow::{Result, anyhow, bail};
+use reqwest::header::{HeaderMap, LINK, RETRY_AFTER};
+use reqwest::{Client, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Display;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 use tokio::time::sleep;
+use tracing::{Instrument, Span, debug, info, instrument, trace, warn};
+use tracing_indicatif::span_ext::IndicatifSpanExt;
 
+use crate::logging::progress;
+
+mod cache;
+mod rate_limit;
+
+use cache::SourceCache;
+pub use cache::{FetchMode, PageCache};
+use rate_limit::{RateLimiter, parse_rate_limit};
+
+/// Page number pagination body used by Flavortown and SoM
 #[derive(Deserialize, Clone)]
 pub struct Pagination {
-    current_page: u64,
-    total_pages: u64,
-    total_count: u64,
-    next_page: u64,
+    pub current_page: u64,
+    pub total_pages: u64,
+    pub total_count: u64,
+    pub next_page: u64,
 }
 
-pub trait Pagintated: for<'a> Deserialize<'a> {
-    const ROOT: &'static str;
-    type Data: for<'a> Deserialize<'a> + Clone + Send + Sync + 'static;
+impl Pagination {
+    pub fn meta(&self) -> PageMeta {
+        PageMeta {
+            total_pages: Some(self.total_pages),
+            total_count: Some(self.total_count),
+            ..Default::default()
+        }
+    }
+}
 
-    fn page(self) -> Vec<Self::Data>;
-    fn pagination(&self) -> &Pagination;
+/// What a page says about the other pages, only what the source's strategy needs has to be set
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct PageMeta {
+    pub total_pages: Option<u64>,
+    pub total_count: Option<u64>,
+    pub next_cursor: Option<String>,
+    pub next_link: Option<String>, // filled in from the Link header
 }
 
-pub async fn concurrent_pagintated_retry_fetch<P: Pagintated>(
-    client: &Client,
-) -> Result<Vec<P::Data>> {
-    let root = P::ROOT;
+/// Selects one page of a source
+#[derive(Clone, Debug)]
+pub enum PageRequest {
+    /// Extra query parameters on the source's url
+    Query(Vec<(String, String)>),
+    /// A full url, Link headers hand these out
+    Url(String),
+}
 
-    println!("[fetch] starting paginated fetch for {}", root);
+impl PageRequest {
+    fn query(params: &[(&str, impl Display)]) -> Self {
+        Self::Query(
+            params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
 
-    let first = fetch_single_wrapped::<5, P, _>(client, format!("{root}?page=1")).await?;
+    fn url<S: Source>(&self, source: &S) -> Result<Url> {
+        Ok(match self {
+            Self::Query(params) => {
+                Url::parse_with_params(source.url(), source.query().iter().chain(params))?
+            }
+            Self::Url(url) => Url::parse(url)?,
+        })
+    }
+}
 
-    let Pagination {
-        total_pages,
-        total_count,
-        ..
-    } = first.pagination();
+/// What to fetch after a page
+pub enum Next {
+    /// Every remaining page is known up front, they are fetched concurrently
+    All(Vec<PageRequest>),
+    /// Only the next page is known, pages are followed one by one
+    One(PageRequest),
+    Done,
+}
 
-    println!(
-        "[fetch] first page fetched: total_pages={}, total_count={}",
-        total_pages, total_count
-    );
+pub trait PaginationStrategy {
+    fn first(&self) -> PageRequest;
 
-    let total_pages = *total_pages as usize;
+    /// `index` is the page's position starting at 0 and `items` how many items it had
+    fn after(&self, index: usize, items: usize, meta: &PageMeta) -> Next;
+}
 
-    let mut results = vec![None; total_pages];
-    results[0] = Some(first.page());
+/// Where a source puts new items, incremental fetches only look at that end
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Order {
+    /// New items are added after the last page
+    OldestFirst,
+    /// New items are added in front and push everything else back a page
+    NewestFirst,
+}
 
-    let results = Arc::new(RwLock::new(results));
+/// `?page=1`, `?page=2`, ... up to the total page count in the body
+pub struct PageNumber {
+    pub param: &'static str,
+}
 
-    let mut pending_pages: Vec<usize> = (2..=total_pages).collect();
+impl Default for PageNumber {
+    fn default() -> Self {
+        Self { param: "page" }
+    }
+}
 
-    let (retry_tx, mut retry_rx) = mpsc::channel::<Duration>(1);
+impl PaginationStrategy for PageNumber {
+    fn first(&self) -> PageRequest {
+        PageRequest::query(&[(self.param, 1)])
+    }
 
-    while !pending_pages.is_empty() {
-        println!(
-            "[round] starting round with {} pending pages: {:?}",
-            pending_pages.len(),
-            pending_pages,
-        );
+    fn after(&self, index: usize, items: usize, meta: &PageMeta) -> Next {
+        let page = index as u64 + 1;
+
+        match meta.total_pages {
+            Some(total) => Next::All(
+                (page + 1..=total)
+                    .map(|page| PageRequest::query(&[(self.param, page)]))
+                    .collect(),
+            ),
+            // no total, keep going until a page comes back empty
+            None if items > 0 => Next::One(PageRequest::query(&[(self.param, page + 1)])),
+            None => Next::Done,
+        }
+    }
+}
 
-        let mut join_set = JoinSet::new();
-
-        for &page in &pending_pages {
-            let url = format!("{root}?page={page}");
-            let client = client.clone();
-            let retry_tx = retry_tx.clone();
-            let results = results.clone();
-
-            println!("[spawn] spawning task for page {}", page);
-            join_set.spawn(async move {
-                let result = fetch_single::<P>(client, &url, retry_tx).await;
-                let mut results_guard = results.write().await;
-                results_guard[page - 1] = result;
-            });
-        }
-
-        // Race: either all complete or a retry is triggered
-        loop {
-            tokio::select! {
-                Some(result) = join_set.join_next() => {
-                    if result.is_err() {
-                        // Task panicked or was cancelled
-                        continue;
-                    }
+/// `?offset=0&limit=100`, `?offset=100&limit=100`, ...
+pub struct OffsetLimit {
+    pub offset: &'static str,
+    pub limit: &'static str,
+    pub per_page: u64,
+}
 
-                    if join_set.is_empty() {
-                        println!("[round] all tasks completed successfully this round");
-                        pending_pages.clear();
-                        break;
-                    }
-                }
-                Some(duration) = retry_rx.recv() => {
-                    println!("[retry] received retry signal: waiting {:?} before retrying", duration);
+impl OffsetLimit {
+    pub fn new(per_page: u64) -> Self {
+        Self {
+            offset: "offset",
+            limit: "limit",
+            per_page,
+        }
+    }
 
-                    join_set.shutdown().await;
-                    sleep(duration).await;
+    fn page(&self, offset: u64) -> PageRequest {
+        PageRequest::query(&[(self.offset, offset), (self.limit, self.per_page)])
+    }
+}
 
-                    // Update pending list - only retry URLs that didn't complete
-                    let results_guard = results.read().await;
-                    pending_pages = results_guard.iter()
-                        .enumerate()
-                        .filter_map(|(i, r)| if r.is_none() { Some(i + 1) } else { None })
-                        .collect();
+impl PaginationStrategy for OffsetLimit {
+    fn first(&self) -> PageRequest {
+        self.page(0)
+    }
 
-                    break;
-                }
-            }
+    fn after(&self, index: usize, items: usize, meta: &PageMeta) -> Next {
+        let offset = (index as u64 + 1) * self.per_page;
+
+        match meta.total_count {
+            Some(total) => Next::All(
+                (offset..total)
+                    .step_by(self.per_page as usize)
+                    .map(|offset| self.page(offset))
+                    .collect(),
+            ),
+            // a short page is the last one
+            None if items as u64 >= self.per_page => Next::One(self.page(offset)),
+            None => Next::Done,
         }
     }
+}
 
-    let final_results = results.read().await;
-    let final_results: Vec<P::Data> = final_results.iter().flatten().flatten().cloned().collect();
+/// `?cursor=...` with the cursor from the previous page's body
+pub struct Cursor {
+    pub param: &'static str,
+}
 
-    println!(
-        "[fetch] completed paginated fetch: total_items={} (pages={})",
-        final_results.len(),
-        total_pages,
-    );
+impl Default for Cursor {
+    fn default() -> Self {
+        Self { param: "cursor" }
+    }
+}
 
-    Ok(final_results)
+impl PaginationStrategy for Cursor {
+    fn first(&self) -> PageRequest {
+        PageRequest::Query(vec![])
+    }
+
+    fn after(&self, _: usize, _: usize, meta: &PageMeta) -> Next {
+        match &meta.next_cursor {
+            Some(cursor) => Next::One(PageRequest::query(&[(self.param, cursor)])),
+            None => Next::Done,
+        }
+    }
 }
 
-/// Fetches a single URL with retry-after detection.
-///
-/// Sends a retry signal via channel if a 429 with Retry-After is encountered.
-/// The channel's capacity of 1 ensures only the first retry signal is processed.
-async fn fetch_single<P: Pagintated>(
-    client: reqwest::Client,
-    url: &str,
-    retry_tx: mpsc::Sender<Duration>,
-) -> Option<Vec<P::Data>> {
-    let response = client.get(url).send().await.ok()?;
-    let status = response.status();
-
-    println!("[request] GET {} -> {}", url, status);
-
-    match status {
-        StatusCode::TOO_MANY_REQUESTS => {
-            println!("[429] rate limited for {}", url);
-            if let Some(duration) = parse_retry_after(response.headers().get(RETRY_AFTER)) {
-                println!("[429] retry-after = {:?} for {}", duration, url);
-                let _ = retry_tx.try_send(duration);
-            }
-            None
+/// `Link: <https://...>; rel="next"`, the GitHub way
+pub struct LinkHeader;
+
+impl PaginationStrategy for LinkHeader {
+    fn first(&self) -> PageRequest {
+        PageRequest::Query(vec![])
+    }
+
+    fn after(&self, _: usize, _: usize, meta: &PageMeta) -> Next {
+        match &meta.next_link {
+            Some(link) => Next::One(PageRequest::Url(link.clone())),
+            None => Next::Done,
         }
-        status if status.is_success() => {
-            println!("[success] parsed JSON for {}", url);
-            response.json::<P>().await.ok().map(|x| x.page())
+    }
+}
+
+/// `rel="next"` target of a Link header
+fn parse_link_next(header: Option<&reqwest::header::HeaderValue>) -> Option<String> {
+    let header = header?.to_str().ok()?;
+
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params.split(';').any(|param| {
+            param
+                .trim()
+                .strip_prefix("rel=")
+                .is_some_and(|rel| rel.trim_matches('"').split(' ').any(|r| r == "next"))
+        });
+
+        is_next.then(|| {
+            target
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+/// A paginated API endpoint
+pub trait Source: Send + Sync + 'static {
+    /// Body of one page
+    type Page: for<'a> Deserialize<'a>;
+    type Data: Serialize + DeserializeOwned + Clone + Send + Sync + 'static;
+
+    fn url(&self) -> &str;
+
+    /// Sent with every page, filters and page sizes go here
+    fn query(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    fn pagination(&self) -> impl PaginationStrategy;
+
+    fn order(&self) -> Order;
+
+    fn items(page: Self::Page) -> Vec<Self::Data>;
+
+    /// Tells items apart across fetches, an incremental fetch of a newest first source stops at
+    /// the first page without new ones
+    fn id(item: &Self::Data) -> String;
+
+    fn meta(_page: &Self::Page) -> PageMeta {
+        PageMeta::default()
+    }
+}
+
+fn meta<S: Source>(page: &S::Page, headers: &HeaderMap) -> PageMeta {
+    PageMeta {
+        next_link: parse_link_next(headers.get(LINK)),
+        ..S::meta(page)
+    }
+}
+
+/// How hard a source is hit
+#[derive(Clone, Debug)]
+pub struct Limits {
+    /// Requests in flight at once
+    pub concurrency: usize,
+    /// Requests per second, 429s and `X-RateLimit-*` headers lower it
+    pub rate: f64,
+    /// Requests that can go out back to back after a quiet period
+    pub burst: f64,
+    /// Attempts per page in a round
+    pub retries: usize,
+    /// Rounds over the pages that are still missing before giving up
+    pub max_rounds: usize,
+    /// Backoff after the first failed attempt, doubled after every one after that
+    pub backoff: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            rate: 10.,
+            burst: 10.,
+            retries: 5,
+            max_rounds: 5,
+            backoff: Duration::from_millis(500),
         }
-        _ => {
-            println!("[error] unexpected status {} for {}", status, url);
-            None
+    }
+}
+
+impl Limits {
+    /// Exponential backoff with jitter, so failed requests don't all come back at the same time
+    fn backoff(&self, attempt: usize) -> Duration {
+        let backoff = self.backoff * 2_u32.pow(attempt.saturating_sub(1).min(16) as u32);
+        backoff / 2 + backoff.mul_f64(rand::random::<f64>() / 2.)
+    }
+}
+
+#[instrument(name = "fetch", skip_all, fields(source = source.url()))]
+pub async fn concurrent_pagintated_retry_fetch<S: Source>(
+    client: &Client,
+    source: &S,
+    cache: &PageCache,
+    limits: &Limits,
+) -> Result<Vec<S::Data>> {
+    let strategy = source.pagination();
+    let cache = cache.source(source).await?;
+    let limiter = Arc::new(RateLimiter::new(limits.rate, limits.burst));
+
+    progress(&Span::current(), None);
+    info!("starting paginated fetch");
+
+    let fetch = Fetch {
+        client,
+        cache: &cache,
+        limiter: &limiter,
+        limits,
+    };
+
+    if cache.incremental() && source.order() == Order::NewestFirst {
+        return fetch.newest_first(source, &strategy).await;
+    }
+
+    // an oldest first source never changes its first page, if it did every cached page is off
+    let before = match source.order() {
+        Order::OldestFirst if cache.incremental() => cache.read::<S::Data>(0).await,
+        _ => None,
+    };
+
+    let (first, meta) = fetch.cached::<S>(0, strategy.first().url(source)?).await?;
+
+    let changed = before.is_some_and(|(before, _)| {
+        let first = first.iter().take(before.len()).map(S::id);
+        !before.iter().map(S::id).eq(first)
+    });
+
+    let refetch;
+    let fetch = if changed {
+        warn!("first page changed since the last fetch, fetching every page again");
+        refetch = cache.refetch();
+        Fetch {
+            cache: &refetch,
+            ..fetch
+        }
+    } else {
+        fetch
+    };
+
+    progress(&Span::current(), meta.total_pages);
+    info!(
+        total_pages = meta.total_pages,
+        total_count = meta.total_count,
+        "first page fetched"
+    );
+
+    let mut next = strategy.after(0, first.len(), &meta);
+    let mut pages = vec![first];
+
+    loop {
+        match next {
+            Next::All(requests) => {
+                let urls = requests
+                    .iter()
+                    .map(|request| request.url(source))
+                    .collect::<Result<Vec<_>>>()?;
+
+                pages.extend(fetch.concurrent::<S>(pages.len(), urls).await?);
+                break;
+            }
+            Next::One(request) => {
+                let (page, meta) = fetch.cached::<S>(pages.len(), request.url(source)?).await?;
+
+                next = strategy.after(pages.len(), page.len(), &meta);
+                pages.push(page);
+            }
+            Next::Done => break,
         }
     }
+
+    let total_pages = pages.len();
+    let final_results: Vec<S::Data> = pages.into_iter().flatten().collect();
+
+    info!(
+        items = final_results.len(),
+        pages = total_pages,
+        "completed paginated fetch"
+    );
+
+    Ok(final_results)
 }
 
-fn parse_retry_after(header: Option<&reqwest::header::HeaderValue>) -> Option<Duration> {
-    let header = header?;
-    let s = header.to_str().ok()?;
+/// Everything the pages of one source share
+struct Fetch<'a> {
+    client: &'a Client,
+    cache: &'a SourceCache,
+    limiter: &'a Arc<RateLimiter>,
+    limits: &'a Limits,
+}
 
-    if let Ok(seconds) = s.parse::<u64>() {
-        return Some(Duration::from_secs(seconds));
+impl Fetch<'_> {
+    /// Page `index` from the cache, or fetched and cached.
+    async fn cached<S: Source>(&self, index: usize, url: Url) -> Result<(Vec<S::Data>, PageMeta)> {
+        Span::current().pb_inc(1);
+
+        if let Some(page) = self.cache.load(index).await {
+            debug!(page = index, "loaded from cache");
+            return Ok(page);
+        }
+
+        let page = fetch_page::<S>(self.client, &url, self.limiter, self.limits).await?;
+        self.cache.store(index, &page).await;
+
+        Ok(page)
     }
 
-    // api returns in seconds but just in case
-    if let Ok(http_date) = httpdate::parse_http_date(s) {
-        let now = std::time::SystemTime::now();
-        if let Ok(duration) = http_date.duration_since(now) {
-            return Some(duration);
+    /// Incremental fetch of a newest first source. New items shift every page, so no cached page
+    /// can be reused as is: pages are fetched from the start until one has no new items and the
+    /// items the last run cached fill in the rest. The pages with new items go in front of the
+    /// cached ones once the fetch is done, the next run stops at this run's newest item. An
+    /// interrupted run leaves the cache as it was.
+    async fn newest_first<S: Source>(
+        &self,
+        source: &S,
+        strategy: &impl PaginationStrategy,
+    ) -> Result<Vec<S::Data>> {
+        let cached = self.cache.items::<S>().await;
+        let known: HashSet<String> = cached.iter().map(S::id).collect();
+
+        let mut requests = VecDeque::from([strategy.first()]);
+        let mut follow = true;
+        let mut pages = 0;
+        let mut fresh = vec![];
+
+        while let Some(request) = requests.pop_front() {
+            let (page, meta) = fetch_page::<S>(
+                self.client,
+                &request.url(source)?,
+                self.limiter,
+                self.limits,
+            )
+            .await?;
+            Span::current().pb_inc(1);
+
+            let index = pages;
+            let items = page.len();
+            pages += 1;
+
+            if page.iter().all(|item| known.contains(&S::id(item))) {
+                break;
+            }
+
+            if follow {
+                match strategy.after(index, items, &meta) {
+                    Next::All(next) => {
+                        requests.extend(next);
+                        follow = false;
+                    }
+                    Next::One(next) => requests.push_back(next),
+                    Next::Done => {}
+                }
+            }
+
+            fresh.push((page, meta));
         }
+
+        if let Err(err) = self.cache.prepend(&fresh).await {
+            warn!(error = %err, "failed to cache the new pages");
+        }
+
+        let mut items: Vec<S::Data> = fresh.into_iter().flat_map(|(page, _)| page).collect();
+        let seen: HashSet<String> = items.iter().map(S::id).collect();
+        let new = items
+            .iter()
+            .filter(|item| !known.contains(&S::id(item)))
+            .count();
+        info!(new, pages, "fetched up to the cached items");
+
+        items.extend(
+            cached
+                .into_iter()
+                .filter(|item| !seen.contains(&S::id(item))),
+        );
+
+        Ok(items)
     }
 
-    None
+    /// Fetches `urls` with at most `concurrency` requests in flight, in rounds until every page
+    /// made it. Results are in `urls` order, `urls[0]` is page `offset` in the cache.
+    async fn concurrent<S: Source>(
+        &self,
+        offset: usize,
+        urls: Vec<Url>,
+    ) -> Result<Vec<Vec<S::Data>>> {
+        let mut results = Vec::with_capacity(urls.len());
+        for page in 0..urls.len() {
+            results.push(self.cache.load(offset + page).await.map(|(items, _)| items));
+        }
+
+        let pending = |results: &[Option<_>]| -> Vec<usize> {
+            (0..results.len())
+                .filter(|&i| results[i].is_none())
+                .collect()
+        };
+
+        let cached = urls.len() - pending(&results).len();
+        Span::current().pb_inc(cached as u64);
+        info!(cached, pages = urls.len(), "pages loaded from cache");
+
+        let urls = Arc::new(urls);
+        let semaphore = Arc::new(Semaphore::new(self.limits.concurrency.max(1)));
+
+        for round in 1..=self.limits.max_rounds {
+            let pending_pages = pending(&results);
+
+            if pending_pages.is_empty() {
+                break;
+            }
+
+            info!(
+                round,
+                max_rounds = self.limits.max_rounds,
+                pending = pending_pages.len(),
+                "starting round"
+            );
+            debug!(?pending_pages);
+
+            let mut join_set = JoinSet::new();
+
+            for page in pending_pages {
+                let permit = semaphore.clone().acquire_owned().await?;
+                let urls = urls.clone();
+                let client = self.client.clone();
+                let cache = self.cache.clone();
+                let limiter = self.limiter.clone();
+                let limits = self.limits.clone();
+
+                trace!(page, "spawning task");
+                join_set.spawn(
+                    async move {
+                        let result = fetch_page::<S>(&client, &urls[page], &limiter, &limits).await;
+
+                        if let Ok(result) = &result {
+                            cache.store(offset + page, result).await;
+                        }
+
+                        drop(permit);
+                        (page, result)
+                    }
+                    .in_current_span(),
+                );
+            }
+
+            while let Some(joined) = join_set.join_next().await {
+                match joined? {
+                    (page, Ok((items, _))) => {
+                        results[page] = Some(items);
+                        Span::current().pb_inc(1);
+                    }
+                    (page, Err(err)) => {
+                        warn!(page, error = %format_args!("{err:#}"), "failed this round")
+                    }
+                }
+            }
+        }
+
+        let missing = pending(&results);
+        if !missing.is_empty() {
+            bail!(
+                "{} pages still missing after {} rounds: {:?}",
+                missing.len(),
+                self.limits.max_rounds,
+                missing
+            );
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
 }
 
-async fn fetch_single_wrapped<const RETRIES: usize, P: Pagintated, T: AsRef<str>>(
-    client: &reqwest::Client,
-    url: T,
-) -> Result<P> {
-    let url = url.as_ref();
+/// Fetches a page, waiting for the rate limiter before every attempt.
+///
+/// A 429 pauses the limiter for every request to the source, other failures back off just this
+/// request. Gives up after `retries` attempts.
+async fn fetch_page<S: Source>(
+    client: &Client,
+    url: &Url,
+    limiter: &RateLimiter,
+    limits: &Limits,
+) -> Result<(Vec<S::Data>, PageMeta)> {
     let mut attempt = 0;
 
     loop {
-        let response = client.get(url).send().await;
-
-        match response {
-            Ok(resp) => {
-                if resp.status() == StatusCode::TOO_MANY_REQUESTS
-                    && let Some(delay_secs) = parse_retry_after(resp.headers().get(RETRY_AFTER))
-                {
-                    println!(
-                        "[rate-limit] attempt {}/{} — waiting {:?} before retry",
-                        attempt + 1,
-                        RETRIES,
-                        delay_secs
-                    );
-
-                    sleep(delay_secs).await;
-                    continue;
+        attempt += 1;
+        limiter.acquire().await;
+
+        let (err, rate_limited) = match client.get(url.clone()).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let headers = response.headers().clone();
+
+                debug!(%url, %status, "GET");
+
+                match parse_rate_limit(&headers) {
+                    Some((remaining, reset)) => limiter.observe(remaining, reset),
+                    None if status.is_success() => limiter.recover(),
+                    None => {}
                 }
 
-                match resp.json::<P>().await {
-                    Ok(json) => {
-                        println!("[success] fetched {} after {} attempt(s)", url, attempt + 1);
-                        return Ok(json);
-                    }
-                    Err(err) => {
-                        attempt += 1;
-                        println!(
-                            "[error] attempt {}/{} — JSON parse failed: {}",
-                            attempt, RETRIES, err
-                        );
-
-                        if attempt >= RETRIES {
-                            return Err(anyhow!(
-                                "JSON parse error after {} attempts: {}",
-                                RETRIES,
-                                err
-                            ));
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    let wait = parse_retry_after(headers.get(RETRY_AFTER))
+                        .unwrap_or_else(|| limits.backoff(attempt));
+
+                    limiter.throttle(wait);
+                    warn!(%url, ?wait, rate = limiter.rate(), "rate limited, pausing");
+
+                    (anyhow!("rate limited"), true)
+                } else if !status.is_success() {
+                    (anyhow!("unexpected status {}", status), false)
+                } else {
+                    match response.json::<S::Page>().await {
+                        Ok(page) => {
+                            debug!(%url, attempt, "fetched");
+                            let meta = meta::<S>(&page, &headers);
+                            return Ok((S::items(page), meta));
                         }
+                        Err(err) => (anyhow!("JSON parse failed: {}", err), false),
                     }
                 }
             }
-            Err(err) => {
-                attempt += 1;
-                println!(
-                    "[error] attempt {}/{} — request failed: {}",
-                    attempt, RETRIES, err
-                );
+            Err(err) => (anyhow!("request failed: {}", err), false),
+        };
 
-                if attempt >= RETRIES {
-                    return Err(anyhow!(
-                        "Request failed after {} attempts: {}",
-                        RETRIES,
-                        err
-                    ));
-                }
-            }
+        warn!(attempt, retries = limits.retries, %url, error = %err, "attempt failed");
+
+        if attempt >= limits.retries {
+            return Err(err.context(format!("{} failed after {} attempts", url, attempt)));
         }
 
-        let backoff = Duration::from_millis(500 * 2_u64.pow(attempt.saturating_sub(1) as u32));
-        println!(
-            "[retry] backing off for {}ms before next attempt",
-            backoff.as_millis()
-        );
+        // the limiter already waits out a 429
+        if !rate_limited {
+            let backoff = limits.backoff(attempt);
+            debug!(?backoff, "backing off before next attempt");
+
+            sleep(backoff).await;
+        }
+    }
+}
 
-        sleep(backoff).await;
+pub fn parse_retry_after(header: Option<&reqwest::header::HeaderValue>) -> Option<Duration> {
+    let header = header?;
+    let s = header.to_str().ok()?;
+
+    if let Ok(seconds) = s.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
     }
+
+    // api returns in seconds but just in case
+    if let Ok(http_date) = httpdate::parse_http_date(s) {
+        let now = std::time::SystemTime::now();
+        if let Ok(duration) = http_date.duration_since(now) {
+            return Some(duration);
+        }
+    }
+
+    None
 }
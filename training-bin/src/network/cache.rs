@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use bincode::config::standard;
+use bincode::serde::{decode_from_slice, encode_to_vec};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::fs;
+use tracing::warn;
+
+use super::{PageMeta, Source};
+
+/// What to do with the pages an earlier run left in the cache
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FetchMode {
+    /// Throw the cache away and fetch everything
+    Fresh,
+    /// Only fetch pages that aren't cached, picks up an interrupted run
+    Resume,
+    /// Like resume, but the end of the source new items show up at is fetched again, see
+    /// [`Order`](super::Order). Oldest first that's the first page for the new totals, the last cached page and
+    /// everything after it, or every page if the first one changed. Newest first it's every page up to the
+    /// first one without new items.
+    Incremental,
+}
+
+/// Every page is written to its own file as soon as it arrives, one directory per source
+#[derive(Clone)]
+pub struct PageCache {
+    dir: PathBuf,
+    mode: FetchMode,
+}
+
+impl PageCache {
+    pub fn new(dir: impl Into<PathBuf>, mode: FetchMode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    pub(super) async fn source<S: Source>(&self, source: &S) -> Result<SourceCache> {
+        let dir = self.dir.join(key(source));
+
+        if self.mode == FetchMode::Fresh && fs::try_exists(&dir).await? {
+            fs::remove_dir_all(&dir).await?;
+        }
+
+        fs::create_dir_all(&dir).await?;
+
+        let last = match self.mode {
+            FetchMode::Incremental => last_index(&dir).await?,
+            _ => None,
+        };
+
+        Ok(SourceCache {
+            dir,
+            mode: self.mode,
+            last,
+        })
+    }
+}
+
+/// `https://flavortown.hackclub.com/api/v1/devlogs` -> `flavortown.hackclub.com_api_v1_devlogs`,
+/// the query is part of the key so differently filtered fetches don't mix
+fn key<S: Source>(source: &S) -> String {
+    let url = source.url();
+    let mut key = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .to_string();
+
+    for (name, value) in source.query() {
+        key.push_str(&format!("_{name}={value}"));
+    }
+
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '=') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Highest page index in `dir`
+async fn last_index(dir: &Path) -> Result<Option<usize>> {
+    let mut entries = fs::read_dir(dir).await?;
+    let mut last = None;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let index = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_suffix(".page"))
+            .and_then(|index| index.parse::<usize>().ok());
+
+        last = last.max(index);
+    }
+
+    Ok(last)
+}
+
+#[derive(Clone)]
+pub(super) struct SourceCache {
+    dir: PathBuf,
+    mode: FetchMode,
+    last: Option<usize>,
+}
+
+impl SourceCache {
+    fn path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{index}.page"))
+    }
+
+    /// An incremental fetch with pages from an earlier run
+    pub fn incremental(&self) -> bool {
+        self.mode == FetchMode::Incremental && self.last.is_some()
+    }
+
+    /// The same cache with every page fetched again
+    pub fn refetch(&self) -> Self {
+        Self {
+            mode: FetchMode::Fresh,
+            ..self.clone()
+        }
+    }
+
+    /// The cached page, unless the mode wants it fetched again
+    pub async fn load<D: DeserializeOwned>(&self, index: usize) -> Option<(Vec<D>, PageMeta)> {
+        let reuse = match self.mode {
+            FetchMode::Fresh => false,
+            FetchMode::Resume => true,
+            FetchMode::Incremental => index != 0 && Some(index) != self.last,
+        };
+
+        if reuse { self.read(index).await } else { None }
+    }
+
+    /// Items of every cached page in order, pages an interrupted run missed are skipped and an
+    /// item on two pages is only kept the first time
+    pub async fn items<S: Source>(&self) -> Vec<S::Data> {
+        let mut items = vec![];
+        let mut seen = HashSet::new();
+
+        for index in 0..=self.last.unwrap_or(0) {
+            if let Some((page, _)) = self.read::<S::Data>(index).await {
+                items.extend(page.into_iter().filter(|item| seen.insert(S::id(item))));
+            }
+        }
+
+        items
+    }
+
+    pub async fn read<D: DeserializeOwned>(&self, index: usize) -> Option<(Vec<D>, PageMeta)> {
+        let data = fs::read(self.path(index)).await.ok()?;
+
+        match decode_from_slice(&data, standard()) {
+            Ok((page, _)) => Some(page),
+            Err(err) => {
+                warn!(page = index, error = %err, "ignoring unreadable cached page");
+                None
+            }
+        }
+    }
+
+    /// Puts `pages` in front of the cached ones, a newest first source added them since. Pages
+    /// are moved and written from the highest index down: an interrupted run leaves the first
+    /// few missing, never a gap between known items, so the next incremental run still fetches
+    /// up to them.
+    pub async fn prepend<D: Serialize>(&self, pages: &[(Vec<D>, PageMeta)]) -> Result<()> {
+        if pages.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(last) = last_index(&self.dir).await? {
+            for index in (0..=last).rev() {
+                let path = self.path(index);
+
+                if fs::try_exists(&path).await? {
+                    fs::rename(&path, self.path(index + pages.len())).await?;
+                }
+            }
+        }
+
+        for (index, page) in pages.iter().enumerate().rev() {
+            self.write(index, page).await?;
+        }
+
+        Ok(())
+    }
+
+    /// A page that can't be written is fetched again next time, no reason to fail the fetch
+    pub async fn store<D: Serialize>(&self, index: usize, page: &(Vec<D>, PageMeta)) {
+        if let Err(err) = self.write(index, page).await {
+            warn!(page = index, error = %err, "failed to cache page");
+        }
+    }
+
+    async fn write<D: Serialize>(&self, index: usize, page: &(Vec<D>, PageMeta)) -> Result<()> {
+        let path = self.path(index);
+
+        // written next to it and renamed, an interrupted write never looks like a finished page
+        let partial = path.with_extension("partial");
+
+        fs::write(&partial, encode_to_vec(page, standard())?).await?;
+        fs::rename(&partial, &path).await?;
+
+        Ok(())
+    }
+}
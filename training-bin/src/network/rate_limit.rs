@@ -0,0 +1,133 @@
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+use tokio::time::{Instant, sleep};
+
+/// Never slow down below one request every 10 seconds
+const MIN_RATE: f64 = 0.1;
+
+/// Token bucket shared by every request to a source. 429s pause it and halve the rate, successes
+/// bring the rate back up and `X-RateLimit-*` headers set it directly.
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+    max_rate: f64,
+    burst: f64,
+}
+
+struct Bucket {
+    tokens: f64,
+    rate: f64, // tokens per second
+    updated: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(rate: f64, burst: f64) -> Self {
+        let rate = rate.max(MIN_RATE);
+        let burst = burst.max(1.);
+
+        Self {
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                rate,
+                updated: Instant::now(),
+                paused_until: None,
+            }),
+            max_rate: rate,
+            burst,
+        }
+    }
+
+    /// Waits until a request can go out
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().expect("not poisoned");
+                let now = Instant::now();
+
+                match bucket.paused_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        bucket.paused_until = None;
+                        bucket.tokens = (bucket.tokens
+                            + (now - bucket.updated).as_secs_f64() * bucket.rate)
+                            .min(self.burst);
+                        bucket.updated = now;
+
+                        if bucket.tokens >= 1. {
+                            bucket.tokens -= 1.;
+                            return;
+                        }
+
+                        Duration::from_secs_f64((1. - bucket.tokens) / bucket.rate)
+                    }
+                }
+            };
+
+            sleep(wait).await;
+        }
+    }
+
+    /// A 429, nothing goes out for `wait` and the rate is halved
+    pub fn throttle(&self, wait: Duration) {
+        let mut bucket = self.bucket.lock().expect("not poisoned");
+        let now = Instant::now();
+        let until = now + wait;
+
+        // the other requests in flight get the same 429, only the first one counts
+        match bucket.paused_until {
+            Some(paused) if paused > now => bucket.paused_until = Some(paused.max(until)),
+            _ => {
+                bucket.paused_until = Some(until);
+                bucket.rate = (bucket.rate / 2.).max(MIN_RATE);
+            }
+        }
+
+        bucket.tokens = 0.;
+        bucket.updated = until;
+    }
+
+    /// `remaining` requests are left until the window resets in `reset`
+    pub fn observe(&self, remaining: u64, reset: Duration) {
+        if remaining == 0 {
+            self.throttle(reset);
+            return;
+        }
+
+        let mut bucket = self.bucket.lock().expect("not poisoned");
+        let rate = remaining as f64 / reset.as_secs_f64().max(1.);
+
+        bucket.rate = rate.clamp(MIN_RATE, self.max_rate);
+    }
+
+    /// A success without rate limit headers, creep back up to the configured rate
+    pub fn recover(&self) {
+        let mut bucket = self.bucket.lock().expect("not poisoned");
+
+        bucket.rate = (bucket.rate + self.max_rate / 20.).min(self.max_rate);
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.bucket.lock().expect("not poisoned").rate
+    }
+}
+
+/// `X-RateLimit-Remaining` and `X-RateLimit-Reset`. The reset is either seconds until the window
+/// resets or, like GitHub does it, a unix timestamp.
+pub fn parse_rate_limit(headers: &HeaderMap) -> Option<(u64, Duration)> {
+    let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+
+    let remaining = header("x-ratelimit-remaining")?;
+    let reset = header("x-ratelimit-reset")?;
+
+    // nobody has a rate limit window longer than ~30 years
+    let reset = if reset > 1_000_000_000 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        reset.saturating_sub(now)
+    } else {
+        reset
+    };
+
+    Some((remaining, Duration::from_secs(reset)))
+}
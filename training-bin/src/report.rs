@@ -0,0 +1,246 @@
+//! AI share over time, split by where the texts came from. Written out as CSV and drawn as an SVG
+//! chart for the demo page.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+
+use time::{Date, Duration};
+
+use crate::document::{Corpus, Document, Kind};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bucket {
+    Day,
+    /// Weeks start on monday
+    Week,
+}
+
+impl Bucket {
+    pub fn start(self, date: Date) -> Date {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(date.weekday().number_days_from_monday().into()),
+        }
+    }
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Day => "day",
+            Self::Week => "week",
+        })
+    }
+}
+
+/// What a document gets counted under, next to the total of everything
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Series {
+    All,
+    Projects,
+    Devlogs,
+    Flavortown,
+    Som,
+}
+
+impl Series {
+    pub fn of(document: &Document) -> Vec<Self> {
+        let mut series = vec![Self::All];
+
+        match document.kind {
+            Some(Kind::Project) => series.push(Self::Projects),
+            Some(Kind::Devlog) => series.push(Self::Devlogs),
+            None => {}
+        }
+
+        match document.source {
+            Corpus::Flavortown => series.push(Self::Flavortown),
+            Corpus::Som => series.push(Self::Som),
+            Corpus::Ai => {}
+        }
+
+        series
+    }
+
+    /// Full class names so tailwind finds them in the generated page
+    fn color(self) -> &'static str {
+        match self {
+            Self::All => "text-blue-600 dark:text-blue-400",
+            Self::Projects => "text-green-600 dark:text-green-400",
+            Self::Devlogs => "text-purple-600 dark:text-purple-400",
+            Self::Flavortown => "text-orange-600 dark:text-orange-400",
+            Self::Som => "text-pink-600 dark:text-pink-400",
+        }
+    }
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::All => "all",
+            Self::Projects => "projects",
+            Self::Devlogs => "devlogs",
+            Self::Flavortown => "flavortown",
+            Self::Som => "som",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Share {
+    pub ai: usize,
+    pub total: usize,
+}
+
+impl Share {
+    pub fn percent(&self) -> f64 {
+        self.ai as f64 * 100. / self.total.max(1) as f64
+    }
+}
+
+pub struct TimeSeries {
+    pub bucket: Bucket,
+    pub shares: BTreeMap<(Series, Date), Share>,
+    /// Documents without a timestamp, they can't be placed anywhere
+    pub undated: usize,
+}
+
+impl TimeSeries {
+    /// `predictions` are the documents and whether they landed in the AI cluster
+    pub fn new<'a>(
+        bucket: Bucket,
+        predictions: impl IntoIterator<Item = (&'a Document, bool)>,
+    ) -> Self {
+        let mut shares: BTreeMap<(Series, Date), Share> = BTreeMap::new();
+        let mut undated = 0;
+
+        for (document, ai) in predictions {
+            let Some(created_at) = document.created_at else {
+                undated += 1;
+                continue;
+            };
+
+            let date = bucket.start(created_at.date());
+
+            for series in Series::of(document) {
+                let share = shares.entry((series, date)).or_default();
+                share.ai += usize::from(ai);
+                share.total += 1;
+            }
+        }
+
+        Self {
+            bucket,
+            shares,
+            undated,
+        }
+    }
+
+    /// Every bucket that has at least one document, oldest first
+    pub fn dates(&self) -> Vec<Date> {
+        let dates: BTreeSet<Date> = self.shares.keys().map(|&(_, date)| date).collect();
+        dates.into_iter().collect()
+    }
+
+    pub fn series(&self) -> Vec<Series> {
+        let series: BTreeSet<Series> = self.shares.keys().map(|&(series, _)| series).collect();
+        series.into_iter().collect()
+    }
+
+    /// `day,series,ai,total,ai_percent`, one line per bucket and series
+    pub fn csv(&self) -> String {
+        let mut csv = format!("{},series,ai,total,ai_percent\n", self.bucket);
+
+        for (date, series, share) in self.rows() {
+            writeln!(
+                csv,
+                "{date},{series},{},{},{:.2}",
+                share.ai,
+                share.total,
+                share.percent()
+            )
+            .expect("writing to a string");
+        }
+
+        csv
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (Date, Series, Share)> + '_ {
+        let series = self.series();
+
+        self.dates().into_iter().flat_map(move |date| {
+            series.clone().into_iter().filter_map(move |series| {
+                let share = self.shares.get(&(series, date))?;
+                Some((date, series, *share))
+            })
+        })
+    }
+
+    /// Line chart of the AI % per series, an empty string when there is nothing to draw
+    pub fn svg(&self) -> String {
+        const WIDTH: f64 = 900.;
+        const HEIGHT: f64 = 300.;
+        const PAD: f64 = 40.;
+
+        let dates = self.dates();
+
+        if dates.is_empty() {
+            return String::new();
+        }
+
+        let max = self
+            .shares
+            .values()
+            .map(Share::percent)
+            .fold(1., f64::max)
+            .ceil();
+        let step = (WIDTH - 2. * PAD) / (dates.len().max(2) - 1) as f64;
+        let x = |i: usize| PAD + i as f64 * step;
+        let y = |percent: f64| HEIGHT - PAD - percent / max * (HEIGHT - 2. * PAD);
+
+        let mut svg = format!(
+            r#"<svg viewBox="0 0 {WIDTH} {HEIGHT}" class="w-full h-auto text-sm" role="img">
+          <line x1="{PAD}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="currentColor" stroke-opacity="0.3" />
+          <text x="{PAD}" y="{top}" fill="currentColor" dy="-8">{max}%</text>
+          <text x="{PAD}" y="{bottom}" fill="currentColor" dy="20">{first}</text>
+          <text x="{right}" y="{bottom}" fill="currentColor" dy="20" text-anchor="end">{last}</text>
+"#,
+            bottom = HEIGHT - PAD,
+            right = WIDTH - PAD,
+            top = PAD,
+            first = dates[0],
+            last = dates[dates.len() - 1],
+        );
+
+        for series in self.series() {
+            let points: Vec<String> = dates
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &date)| {
+                    let share = self.shares.get(&(series, date))?;
+                    Some(format!("{:.1},{:.1}", x(i), y(share.percent())))
+                })
+                .collect();
+
+            writeln!(
+                svg,
+                r#"          <polyline class="{}" points="{}" fill="none" stroke="currentColor" stroke-width="2"><title>{series}</title></polyline>"#,
+                series.color(),
+                points.join(" ")
+            )
+            .expect("writing to a string");
+        }
+
+        svg.push_str("        </svg>");
+        svg
+    }
+
+    /// Which color is which line, to go under the chart
+    pub fn legend(&self) -> String {
+        self.series()
+            .into_iter()
+            .map(|series| format!(r#"<span class="{}">{series}</span>"#, series.color()))
+            .collect::<Vec<_>>()
+            .join("\n          ")
+    }
+}
@@ -0,0 +1,162 @@
+//! Predictions grouped by author or project, for moderators. One flagged devlog says little, a
+//! person whose posts are mostly flagged is worth a look.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use crate::document::Document;
+
+/// z for a 95% confidence interval
+const Z: f64 = 1.96;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    Author,
+    Project,
+}
+
+impl GroupBy {
+    pub fn key(self, document: &Document) -> Option<&str> {
+        match self {
+            Self::Author => document.author.as_deref(),
+            Self::Project => document.project.as_deref(),
+        }
+    }
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Author => "author",
+            Self::Project => "project",
+        })
+    }
+}
+
+/// One prediction, `probability` is the confidence of the AI cluster and `flagged` whether the
+/// document landed in it
+#[derive(Clone, Copy, Debug)]
+pub struct Prediction<'a> {
+    pub document: &'a Document,
+    pub probability: f64,
+    pub flagged: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub key: String,
+    pub posts: usize,
+    pub flagged: usize,
+    /// 95% Wilson interval of the flagged share
+    pub interval: (f64, f64),
+    pub mean: f64,
+    pub max: f64,
+}
+
+impl Group {
+    pub fn flagged_share(&self) -> f64 {
+        self.flagged as f64 / self.posts.max(1) as f64
+    }
+}
+
+/// Wilson score interval of `k` successes in `n` trials, stays sensible for tiny `n` and shares
+/// close to 0 or 1
+pub fn wilson_interval(k: usize, n: usize) -> (f64, f64) {
+    if n == 0 {
+        return (0., 1.);
+    }
+
+    let n = n as f64;
+    let p = k as f64 / n;
+    let z2 = Z * Z;
+
+    let denominator = 1. + z2 / n;
+    let center = (p + z2 / (2. * n)) / denominator;
+    let half = Z * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt() / denominator;
+
+    ((center - half).max(0.), (center + half).min(1.))
+}
+
+/// Groups with at least `min_posts` documents, most likely AI first. Ranked by the lower bound of
+/// the flagged share, so three out of three doesn't beat forty out of fifty.
+pub fn review_queue<'a>(
+    group_by: GroupBy,
+    predictions: impl IntoIterator<Item = Prediction<'a>>,
+    min_posts: usize,
+) -> Vec<Group> {
+    let mut groups: HashMap<&str, Vec<Prediction>> = HashMap::new();
+
+    for prediction in predictions {
+        if let Some(key) = group_by.key(prediction.document) {
+            groups.entry(key).or_default().push(prediction);
+        }
+    }
+
+    let mut queue: Vec<Group> = groups
+        .into_iter()
+        .filter(|(_, predictions)| predictions.len() >= min_posts.max(1))
+        .map(|(key, predictions)| {
+            let posts = predictions.len();
+            let flagged = predictions.iter().filter(|p| p.flagged).count();
+            let probabilities = predictions.iter().map(|p| p.probability);
+
+            Group {
+                key: key.to_string(),
+                posts,
+                flagged,
+                interval: wilson_interval(flagged, posts),
+                mean: probabilities.clone().sum::<f64>() / posts as f64,
+                max: probabilities.fold(0., f64::max),
+            }
+        })
+        .collect();
+
+    queue.sort_by(|a, b| {
+        b.interval
+            .0
+            .total_cmp(&a.interval.0)
+            .then(b.mean.total_cmp(&a.mean))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+
+    queue
+}
+
+/// `author,posts,flagged,flagged_low,flagged_high,mean_ai,max_ai`, in queue order
+pub fn csv(group_by: GroupBy, queue: &[Group]) -> String {
+    let mut csv = format!("{group_by},posts,flagged,flagged_low,flagged_high,mean_ai,max_ai\n");
+
+    for group in queue {
+        writeln!(
+            csv,
+            "{},{},{},{:.4},{:.4},{:.4},{:.4}",
+            group.key,
+            group.posts,
+            group.flagged,
+            group.interval.0,
+            group.interval.1,
+            group.mean,
+            group.max
+        )
+        .expect("writing to a string");
+    }
+
+    csv
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}/{} flagged ({:.0}%, 95% {:.0}-{:.0}%) mean {:.1}% max {:.1}%",
+            self.key,
+            self.flagged,
+            self.posts,
+            self.flagged_share() * 100.,
+            self.interval.0 * 100.,
+            self.interval.1 * 100.,
+            self.mean * 100.,
+            self.max * 100.
+        )
+    }
+}
@@ -0,0 +1,413 @@
+//! Local SQLite store of the corpora. Documents are upserted instead of rewriting one big bincode
+//! blob, every fetch is recorded as a run with the documents it saw, and computed metrics are
+//! cached by text hash and metrics version.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use bincode::config::standard;
+use bincode::serde::{decode_from_slice, encode_to_vec};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::document::{Corpus, Document, Label, parse_timestamp};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS documents (
+    source TEXT NOT NULL,
+    id TEXT NOT NULL,
+    kind TEXT,
+    author TEXT,
+    created_at TEXT,
+    project TEXT,
+    duration_seconds INTEGER,
+    text TEXT NOT NULL,
+    text_hash TEXT NOT NULL,
+    PRIMARY KEY (source, id)
+);
+
+CREATE TABLE IF NOT EXISTS labels (
+    source TEXT NOT NULL,
+    id TEXT NOT NULL,
+    label TEXT NOT NULL,
+    PRIMARY KEY (source, id)
+);
+
+CREATE TABLE IF NOT EXISTS fetch_runs (
+    id INTEGER PRIMARY KEY,
+    source TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    finished_at TEXT NOT NULL,
+    documents INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS run_documents (
+    run INTEGER NOT NULL REFERENCES fetch_runs (id),
+    source TEXT NOT NULL,
+    id TEXT NOT NULL,
+    PRIMARY KEY (run, source, id)
+);
+
+CREATE TABLE IF NOT EXISTS metrics (
+    text_hash TEXT NOT NULL,
+    version TEXT NOT NULL,
+    metrics BLOB NOT NULL,
+    PRIMARY KEY (text_hash, version)
+);
+";
+
+const SELECT_DOCUMENTS: &str = "
+SELECT d.id, d.source, d.kind, d.author, d.created_at, d.project, d.duration_seconds, d.text, l.label
+FROM documents d
+LEFT JOIN labels l ON l.source = d.source AND l.id = d.id";
+
+/// One fetch, `documents` is how many it returned
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchRun {
+    pub id: i64,
+    pub source: Corpus,
+    pub started_at: OffsetDateTime,
+    pub finished_at: OffsetDateTime,
+    pub documents: usize,
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+/// Hex blake3 of the text, what the metrics cache is keyed by
+pub fn text_hash(text: &str) -> String {
+    blake3::hash(text.as_bytes()).to_hex().to_string()
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        add_missing_columns(&connection)?;
+
+        Ok(Self { connection })
+    }
+
+    /// Adds `documents`, ones that are already stored are updated
+    pub fn insert_documents(&mut self, documents: &[Document]) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        insert(&transaction, documents)?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// Stores what a fetch of `source` returned, as a run that can be looked at later with
+    /// [`Store::snapshot`]
+    pub fn record_run(
+        &mut self,
+        source: Corpus,
+        started_at: OffsetDateTime,
+        documents: &[Document],
+    ) -> Result<i64> {
+        let transaction = self.connection.transaction()?;
+        insert(&transaction, documents)?;
+
+        transaction.execute(
+            "INSERT INTO fetch_runs (source, started_at, finished_at, documents)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                source.to_string(),
+                started_at.format(&Rfc3339)?,
+                OffsetDateTime::now_utc().format(&Rfc3339)?,
+                documents.len(),
+            ],
+        )?;
+        let run = transaction.last_insert_rowid();
+
+        {
+            let mut statement = transaction.prepare(
+                "INSERT OR IGNORE INTO run_documents (run, source, id) VALUES (?1, ?2, ?3)",
+            )?;
+
+            for document in documents {
+                statement.execute(params![run, document.source.to_string(), document.id])?;
+            }
+        }
+
+        transaction.commit()?;
+
+        Ok(run)
+    }
+
+    pub fn runs(&self) -> Result<Vec<FetchRun>> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, source, started_at, finished_at, documents FROM fetch_runs ORDER BY id",
+        )?;
+
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, usize>(4)?,
+            ))
+        })?;
+
+        rows.map(|row| {
+            let (id, source, started_at, finished_at, documents) = row?;
+
+            Ok(FetchRun {
+                id,
+                source: source.parse()?,
+                started_at: OffsetDateTime::parse(&started_at, &Rfc3339)?,
+                finished_at: OffsetDateTime::parse(&finished_at, &Rfc3339)?,
+                documents,
+            })
+        })
+        .collect()
+    }
+
+    /// Every stored document of `source`, in the order they were first added
+    pub fn documents(&self, source: Corpus) -> Result<Vec<Document>> {
+        self.select(
+            &format!("{SELECT_DOCUMENTS} WHERE d.source = ?1 ORDER BY d.rowid"),
+            params![source.to_string()],
+        )
+    }
+
+    /// What the latest fetch of `source` returned, or everything stored for it when it was never
+    /// fetched (imported from a `.data` file)
+    pub fn current(&self, source: Corpus) -> Result<Vec<Document>> {
+        let run: Option<i64> = self.connection.query_row(
+            "SELECT MAX(id) FROM fetch_runs WHERE source = ?1",
+            params![source.to_string()],
+            |row| row.get(0),
+        )?;
+
+        match run {
+            Some(run) => self.snapshot(run),
+            None => self.documents(source),
+        }
+    }
+
+    /// The documents `run` returned, as they are stored now
+    pub fn snapshot(&self, run: i64) -> Result<Vec<Document>> {
+        self.select(
+            &format!(
+                "{SELECT_DOCUMENTS}
+                 JOIN run_documents r ON r.source = d.source AND r.id = d.id
+                 WHERE r.run = ?1 ORDER BY d.rowid"
+            ),
+            params![run],
+        )
+    }
+
+    pub fn count(&self, source: Corpus) -> Result<usize> {
+        Ok(self.connection.query_row(
+            "SELECT COUNT(*) FROM documents WHERE source = ?1",
+            params![source.to_string()],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn select(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<Document>> {
+        let mut statement = self.connection.prepare(sql)?;
+
+        let rows = statement.query_map(params, |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<u64>>(6)?,
+                row.get::<_, String>(7)?,
+                row.get::<_, Option<String>>(8)?,
+            ))
+        })?;
+
+        rows.map(|row| {
+            let (id, source, kind, author, created_at, project, duration_seconds, text, label) =
+                row?;
+
+            Ok(Document {
+                id,
+                source: source.parse()?,
+                kind: kind.map(|kind| kind.parse()).transpose()?,
+                author,
+                created_at: parse_timestamp(created_at.as_deref()),
+                project,
+                duration_seconds,
+                text,
+                label: label.map(|label| label.parse()).transpose()?,
+            })
+        })
+        .collect()
+    }
+
+    /// Metrics computed for the text with `text_hash` by metrics `version`
+    pub fn metrics<M: DeserializeOwned>(
+        &self,
+        text_hash: &str,
+        version: &str,
+    ) -> Result<Option<M>> {
+        let blob: Option<Vec<u8>> = self
+            .connection
+            .query_row(
+                "SELECT metrics FROM metrics WHERE text_hash = ?1 AND version = ?2",
+                params![text_hash, version],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        blob.map(|blob| Ok(decode_from_slice(&blob, standard())?.0))
+            .transpose()
+    }
+
+    /// Stores `(text_hash, metrics)` pairs computed by metrics `version`, in one transaction
+    pub fn store_metrics<'a, M: Serialize + 'a>(
+        &mut self,
+        version: &str,
+        metrics: impl IntoIterator<Item = (&'a str, &'a M)>,
+    ) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+
+        {
+            let mut statement = transaction.prepare(
+                "INSERT OR REPLACE INTO metrics (text_hash, version, metrics) VALUES (?1, ?2, ?3)",
+            )?;
+
+            for (text_hash, metrics) in metrics {
+                statement.execute(params![
+                    text_hash,
+                    version,
+                    encode_to_vec(metrics, standard())?
+                ])?;
+            }
+        }
+
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// Deletes the metrics of every version but `versions`, they'll never be read again
+    pub fn retain_metrics(&self, versions: &[&str]) -> Result<usize> {
+        let placeholders = vec!["?"; versions.len()].join(", ");
+
+        Ok(self.connection.execute(
+            &format!("DELETE FROM metrics WHERE version NOT IN ({placeholders})"),
+            rusqlite::params_from_iter(versions),
+        )?)
+    }
+
+    /// Adds the texts of a bincode `Vec<String>` `.data` file, ids are their position in it
+    pub fn import(
+        &mut self,
+        path: impl AsRef<Path>,
+        source: Corpus,
+        label: Option<Label>,
+    ) -> Result<usize> {
+        let texts: Vec<String> = decode_from_slice(&fs::read(path)?, standard())?.0;
+        let documents = Document::from_texts(texts, source, label);
+
+        self.insert_documents(&documents)?;
+
+        Ok(documents.len())
+    }
+
+    /// Writes the texts of `source` as a `.data` file, for anything that still reads those
+    pub fn export(&self, source: Corpus, path: impl AsRef<Path>) -> Result<usize> {
+        let texts: Vec<String> = self
+            .current(source)?
+            .into_iter()
+            .map(|document| document.text)
+            .collect();
+
+        fs::write(path, encode_to_vec(&texts, standard())?)?;
+
+        Ok(texts.len())
+    }
+}
+
+/// Columns added after the first stores were written, `CREATE TABLE IF NOT EXISTS` leaves those
+/// tables as they are
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[("documents", "duration_seconds", "INTEGER")];
+
+fn add_missing_columns(connection: &Connection) -> Result<()> {
+    for (table, column, definition) in ADDED_COLUMNS {
+        let exists: bool = connection.query_row(
+            "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+            params![table, column],
+            |row| row.get(0),
+        )?;
+
+        if !exists {
+            connection.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn insert(connection: &Connection, documents: &[Document]) -> Result<()> {
+    let mut document_statement = connection.prepare(
+        "INSERT INTO documents (
+             source, id, kind, author, created_at, project, duration_seconds, text, text_hash
+         )
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT (source, id) DO UPDATE SET
+             kind = excluded.kind,
+             author = excluded.author,
+             created_at = excluded.created_at,
+             project = excluded.project,
+             duration_seconds = excluded.duration_seconds,
+             text = excluded.text,
+             text_hash = excluded.text_hash",
+    )?;
+    let mut label_statement = connection
+        .prepare("INSERT OR REPLACE INTO labels (source, id, label) VALUES (?1, ?2, ?3)")?;
+
+    for document in documents {
+        let created_at = document
+            .created_at
+            .map(|created_at| created_at.format(&Rfc3339))
+            .transpose()?;
+
+        document_statement.execute(params![
+            document.source.to_string(),
+            document.id,
+            document.kind.map(|kind| kind.to_string()),
+            document.author,
+            created_at,
+            document.project,
+            document.duration_seconds,
+            document.text,
+            text_hash(&document.text),
+        ])?;
+
+        // no label doesn't mean the stored one is wrong
+        if let Some(label) = document.label {
+            label_statement.execute(params![
+                document.source.to_string(),
+                document.id,
+                label.to_string(),
+            ])?;
+        }
+    }
+
+    Ok(())
+}
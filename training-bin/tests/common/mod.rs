@@ -0,0 +1,18 @@
+//! Documents for the tests that don't fetch them. Only the source is set, tests fill in the
+//! fields they look at with `..document(source)`.
+
+use training::document::{Corpus, Document};
+
+pub fn document(source: Corpus) -> Document {
+    Document {
+        id: String::new(),
+        source,
+        kind: None,
+        author: None,
+        created_at: None,
+        project: None,
+        duration_seconds: None,
+        text: String::new(),
+        label: None,
+    }
+}
@@ -0,0 +1,681 @@
+//! The fetch pipeline against a local mock of the API, see `mock/mod.rs`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use reqwest::Client;
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+use time::macros::datetime;
+use training::document::{Corpus, Document, Kind};
+use training::flavortown::fetch_all_from;
+use training::flavortown::sources::Devlogs;
+use training::network::{
+    Cursor, FetchMode, Limits, LinkHeader, OffsetLimit, Order, PageCache, PageMeta,
+    PaginationStrategy, Source, concurrent_pagintated_retry_fetch, parse_retry_after,
+};
+
+mod mock;
+
+use mock::{MockServer, Response, devlogs_page, projects_page};
+
+fn limits() -> Limits {
+    Limits {
+        backoff: Duration::from_millis(1),
+        ..Limits::default()
+    }
+}
+
+/// A fresh cache directory per test
+fn cache(name: &str, mode: FetchMode) -> PageCache {
+    PageCache::new(cache_dir(name), mode)
+}
+
+fn cache_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("training-fetch-{name}-{}", std::process::id()))
+}
+
+fn page_of(path: &str) -> Option<u64> {
+    path.split_once("page=")?.1.parse().ok()
+}
+
+fn bodies(devlogs: &[training::flavortown::sources::Devlog]) -> Vec<String> {
+    devlogs.iter().map(|devlog| devlog.body.clone()).collect()
+}
+
+fn expected(pages: u64, per_page: u64) -> Vec<String> {
+    (1..=pages)
+        .flat_map(|page| (0..per_page).map(move |i| format!("devlogs {page}-{i}")))
+        .collect()
+}
+
+async fn fetch_devlogs(
+    server: &MockServer,
+    cache: &PageCache,
+    limits: &Limits,
+) -> anyhow::Result<Vec<String>> {
+    let devlogs = concurrent_pagintated_retry_fetch(
+        &Client::new(),
+        &Devlogs::new(&server.url),
+        cache,
+        limits,
+    )
+    .await?;
+
+    Ok(bodies(&devlogs))
+}
+
+#[test]
+fn retry_after_in_seconds() {
+    let header = HeaderValue::from_static("120");
+    assert_eq!(
+        parse_retry_after(Some(&header)),
+        Some(Duration::from_secs(120))
+    );
+}
+
+#[test]
+fn retry_after_as_http_date() {
+    let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(30));
+    let header = HeaderValue::from_str(&date).unwrap();
+    let wait = parse_retry_after(Some(&header)).unwrap();
+
+    // http dates only have whole seconds
+    assert!(wait > Duration::from_secs(28) && wait <= Duration::from_secs(30));
+}
+
+#[test]
+fn retry_after_garbage_or_past() {
+    let past = httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(30));
+
+    assert_eq!(parse_retry_after(None), None);
+    assert_eq!(
+        parse_retry_after(Some(&HeaderValue::from_static("soon"))),
+        None
+    );
+    assert_eq!(
+        parse_retry_after(Some(&HeaderValue::from_static("-5"))),
+        None
+    );
+    assert_eq!(
+        parse_retry_after(Some(&HeaderValue::from_str(&past).unwrap())),
+        None
+    );
+}
+
+#[tokio::test]
+async fn pages_keep_their_order() {
+    // later pages answer first
+    let server = MockServer::start(|path, _| {
+        let page = page_of(path).unwrap();
+        Response::json(devlogs_page(page, 8, 3)).delayed(Duration::from_millis(10 * (8 - page)))
+    })
+    .await;
+
+    let devlogs = fetch_devlogs(&server, &cache("order", FetchMode::Fresh), &limits())
+        .await
+        .unwrap();
+
+    assert_eq!(devlogs, expected(8, 3));
+    assert_eq!(server.requests().len(), 8);
+}
+
+#[tokio::test]
+async fn retries_429_with_retry_after_seconds() {
+    let server = MockServer::start(|path, hits| match (page_of(path).unwrap(), hits) {
+        (3, 0) => Response::too_many_requests(1),
+        (page, _) => Response::json(devlogs_page(page, 4, 2)),
+    })
+    .await;
+
+    let devlogs = fetch_devlogs(&server, &cache("429-seconds", FetchMode::Fresh), &limits())
+        .await
+        .unwrap();
+
+    assert_eq!(devlogs, expected(4, 2));
+    assert_eq!(server.hits("/devlogs?page=3"), 2);
+}
+
+#[tokio::test]
+async fn retries_429_with_retry_after_date() {
+    let server = MockServer::start(|path, hits| match (page_of(path).unwrap(), hits) {
+        (2, 0) => Response::too_many_requests_until(SystemTime::now() + Duration::from_secs(2)),
+        (page, _) => Response::json(devlogs_page(page, 4, 2)),
+    })
+    .await;
+
+    let devlogs = fetch_devlogs(&server, &cache("429-date", FetchMode::Fresh), &limits())
+        .await
+        .unwrap();
+
+    assert_eq!(devlogs, expected(4, 2));
+    assert_eq!(server.hits("/devlogs?page=2"), 2);
+}
+
+#[tokio::test]
+async fn retries_malformed_json() {
+    let server = MockServer::start(|path, hits| match (page_of(path).unwrap(), hits) {
+        (1 | 3, 0) => Response::json(r#"{"devlogs": [{"body": "#),
+        (page, _) => Response::json(devlogs_page(page, 3, 2)),
+    })
+    .await;
+
+    let devlogs = fetch_devlogs(&server, &cache("malformed", FetchMode::Fresh), &limits())
+        .await
+        .unwrap();
+
+    assert_eq!(devlogs, expected(3, 2));
+    assert_eq!(server.hits("/devlogs?page=1"), 2);
+    assert_eq!(server.hits("/devlogs?page=3"), 2);
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let server = MockServer::start(|path, hits| match (page_of(path).unwrap(), hits) {
+        (2, 0) => Response::status(500),
+        (2, 1) => Response::status(503),
+        (page, _) => Response::json(devlogs_page(page, 3, 2)),
+    })
+    .await;
+
+    let devlogs = fetch_devlogs(&server, &cache("5xx", FetchMode::Fresh), &limits())
+        .await
+        .unwrap();
+
+    assert_eq!(devlogs, expected(3, 2));
+    assert_eq!(server.hits("/devlogs?page=2"), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_rounds() {
+    let server = MockServer::start(|path, _| match page_of(path).unwrap() {
+        2 => Response::status(502),
+        page => Response::json(devlogs_page(page, 3, 2)),
+    })
+    .await;
+
+    let limits = Limits {
+        retries: 2,
+        max_rounds: 3,
+        ..limits()
+    };
+
+    let result = fetch_devlogs(&server, &cache("give-up", FetchMode::Fresh), &limits).await;
+
+    assert!(result.is_err());
+    assert_eq!(server.hits("/devlogs?page=2"), 6);
+}
+
+#[tokio::test]
+async fn resume_only_fetches_missing_pages() {
+    let fixed = Arc::new(AtomicBool::new(false));
+
+    let server = MockServer::start({
+        let fixed = fixed.clone();
+        move |path, _| match page_of(path).unwrap() {
+            4 if !fixed.load(Ordering::SeqCst) => Response::status(500),
+            page => Response::json(devlogs_page(page, 5, 2)),
+        }
+    })
+    .await;
+
+    let limits = Limits {
+        retries: 1,
+        max_rounds: 1,
+        ..limits()
+    };
+
+    let fresh = cache("resume", FetchMode::Fresh);
+    assert!(fetch_devlogs(&server, &fresh, &limits).await.is_err());
+
+    fixed.store(true, Ordering::SeqCst);
+
+    let resume = PageCache::new(cache_dir("resume"), FetchMode::Resume);
+    let devlogs = fetch_devlogs(&server, &resume, &limits).await.unwrap();
+
+    assert_eq!(devlogs, expected(5, 2));
+    assert_eq!(server.requests()[5..], ["/devlogs?page=4"]);
+}
+
+#[tokio::test]
+async fn incremental_fetches_new_pages() {
+    let total_pages = Arc::new(OnceLock::new());
+
+    let server = MockServer::start({
+        let total_pages = total_pages.clone();
+        move |path, _| {
+            let total = *total_pages.get().unwrap_or(&3);
+            Response::json(devlogs_page(page_of(path).unwrap(), total, 2))
+        }
+    })
+    .await;
+
+    let devlogs = fetch_devlogs(&server, &cache("incremental", FetchMode::Fresh), &limits())
+        .await
+        .unwrap();
+    assert_eq!(devlogs, expected(3, 2));
+
+    total_pages.set(5).unwrap();
+
+    let incremental = PageCache::new(cache_dir("incremental"), FetchMode::Incremental);
+    let devlogs = fetch_devlogs(&server, &incremental, &limits())
+        .await
+        .unwrap();
+
+    assert_eq!(devlogs, expected(5, 2));
+
+    // the first page for the totals, the last cached one and the new ones
+    let mut refetched: Vec<u64> = server.requests()[3..]
+        .iter()
+        .filter_map(|path| page_of(path))
+        .collect();
+    refetched.sort();
+    assert_eq!(refetched, [1, 3, 4, 5]);
+}
+
+#[tokio::test]
+async fn incremental_newest_first_stops_at_known_items() {
+    let posts = Arc::new(Mutex::new(vec!["e", "d", "c", "b", "a"]));
+
+    let server = MockServer::start({
+        let posts = posts.clone();
+        move |path, _| {
+            let posts = posts.lock().unwrap();
+            let offset: usize = path
+                .split_once("offset=")
+                .and_then(|(_, rest)| rest.split('&').next()?.parse().ok())
+                .unwrap();
+            let page: Vec<&str> = posts.iter().skip(offset).take(2).copied().collect();
+
+            forum_page(&page, Some(posts.len() as u64), None)
+        }
+    })
+    .await;
+
+    let forum = Forum {
+        url: format!("{}/posts", server.url),
+        order: Order::NewestFirst,
+        pagination: || OffsetLimit::new(2),
+    };
+
+    let fresh = cache("newest-first", FetchMode::Fresh);
+    let fetched = concurrent_pagintated_retry_fetch(&Client::new(), &forum, &fresh, &limits())
+        .await
+        .unwrap();
+    assert_eq!(fetched, ["e", "d", "c", "b", "a"]);
+
+    // every cached page is a page off now
+    posts.lock().unwrap().splice(0..0, ["g", "f"]);
+
+    let incremental = PageCache::new(cache_dir("newest-first"), FetchMode::Incremental);
+    let fetched =
+        concurrent_pagintated_retry_fetch(&Client::new(), &forum, &incremental, &limits())
+            .await
+            .unwrap();
+
+    assert_eq!(fetched, ["g", "f", "e", "d", "c", "b", "a"]);
+    assert_eq!(
+        server.requests()[3..],
+        [
+            "/posts?sort=newest&offset=0&limit=2",
+            "/posts?sort=newest&offset=2&limit=2"
+        ]
+    );
+
+    // g and f were cached, the next run stops at them
+    posts.lock().unwrap().insert(0, "h");
+
+    let fetched =
+        concurrent_pagintated_retry_fetch(&Client::new(), &forum, &incremental, &limits())
+            .await
+            .unwrap();
+
+    assert_eq!(fetched, ["h", "g", "f", "e", "d", "c", "b", "a"]);
+    assert_eq!(
+        server.requests()[5..],
+        [
+            "/posts?sort=newest&offset=0&limit=2",
+            "/posts?sort=newest&offset=2&limit=2"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn incremental_refetches_when_the_first_page_changed() {
+    let posts = Arc::new(Mutex::new(vec!["e", "d", "c", "b", "a"]));
+
+    // declared oldest first, but new posts show up in front
+    let server = MockServer::start({
+        let posts = posts.clone();
+        move |path, _| {
+            let posts = posts.lock().unwrap();
+            let offset: usize = path
+                .split_once("offset=")
+                .and_then(|(_, rest)| rest.split('&').next()?.parse().ok())
+                .unwrap();
+            let page: Vec<&str> = posts.iter().skip(offset).take(2).copied().collect();
+
+            forum_page(&page, Some(posts.len() as u64), None)
+        }
+    })
+    .await;
+
+    let forum = Forum {
+        url: format!("{}/posts", server.url),
+        order: Order::OldestFirst,
+        pagination: || OffsetLimit::new(2),
+    };
+
+    let fresh = cache("first-page-changed", FetchMode::Fresh);
+    concurrent_pagintated_retry_fetch(&Client::new(), &forum, &fresh, &limits())
+        .await
+        .unwrap();
+
+    posts.lock().unwrap().splice(0..0, ["g", "f"]);
+
+    let incremental = PageCache::new(cache_dir("first-page-changed"), FetchMode::Incremental);
+    let fetched =
+        concurrent_pagintated_retry_fetch(&Client::new(), &forum, &incremental, &limits())
+            .await
+            .unwrap();
+
+    assert_eq!(fetched, ["g", "f", "e", "d", "c", "b", "a"]);
+}
+
+#[derive(Deserialize)]
+struct ForumPage {
+    posts: Vec<String>,
+    total: Option<u64>,
+    next_cursor: Option<String>,
+}
+
+/// A forum-like API, the pagination style and order are picked by the test
+struct Forum<P> {
+    url: String,
+    order: Order,
+    pagination: fn() -> P,
+}
+
+impl<P: PaginationStrategy + 'static> Source for Forum<P> {
+    type Page = ForumPage;
+    type Data = String;
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn query(&self) -> Vec<(String, String)> {
+        let sort = match self.order {
+            Order::OldestFirst => "oldest",
+            Order::NewestFirst => "newest",
+        };
+
+        vec![("sort".into(), sort.into())]
+    }
+
+    fn pagination(&self) -> impl PaginationStrategy {
+        (self.pagination)()
+    }
+
+    fn order(&self) -> Order {
+        self.order
+    }
+
+    fn items(page: ForumPage) -> Vec<String> {
+        page.posts
+    }
+
+    fn id(post: &String) -> String {
+        post.clone()
+    }
+
+    fn meta(page: &ForumPage) -> PageMeta {
+        PageMeta {
+            total_count: page.total,
+            next_cursor: page.next_cursor.clone(),
+            ..PageMeta::default()
+        }
+    }
+}
+
+fn forum_page(posts: &[&str], total: Option<u64>, next_cursor: Option<&str>) -> Response {
+    Response::json(
+        serde_json::json!({ "posts": posts, "total": total, "next_cursor": next_cursor })
+            .to_string(),
+    )
+}
+
+async fn fetch_forum<P: PaginationStrategy + 'static>(
+    server: &MockServer,
+    name: &str,
+    pagination: fn() -> P,
+) -> Vec<String> {
+    let forum = Forum {
+        url: format!("{}/posts", server.url),
+        order: Order::OldestFirst,
+        pagination,
+    };
+
+    concurrent_pagintated_retry_fetch(
+        &Client::new(),
+        &forum,
+        &cache(name, FetchMode::Fresh),
+        &limits(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn follows_link_headers() {
+    let base = Arc::new(OnceLock::<String>::new());
+
+    let server = MockServer::start({
+        let base = base.clone();
+        move |path, _| {
+            let base = base.get().unwrap();
+
+            match path {
+                "/posts?sort=oldest" => forum_page(&["a", "b"], None, None).header(
+                    "Link",
+                    format!(
+                        r#"<{base}/posts?sort=oldest&page=2>; rel="next", <{base}/posts?sort=oldest&page=3>; rel="last""#
+                    ),
+                ),
+                "/posts?sort=oldest&page=2" => forum_page(&["c"], None, None)
+                    .header("Link", format!(r#"<{base}/posts?sort=oldest&page=3>; rel="next""#)),
+                "/posts?sort=oldest&page=3" => forum_page(&["d"], None, None),
+                _ => Response::status(404),
+            }
+        }
+    })
+    .await;
+
+    base.set(server.url.clone()).unwrap();
+
+    let posts = fetch_forum(&server, "link", || LinkHeader).await;
+
+    assert_eq!(posts, ["a", "b", "c", "d"]);
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn follows_cursors() {
+    let server = MockServer::start(|path, _| match path {
+        "/posts?sort=oldest" => forum_page(&["a"], None, Some("x1")),
+        "/posts?sort=oldest&cursor=x1" => forum_page(&["b", "c"], None, Some("x2")),
+        "/posts?sort=oldest&cursor=x2" => forum_page(&["d"], None, None),
+        _ => Response::status(404),
+    })
+    .await;
+
+    let posts = fetch_forum(&server, "cursor", Cursor::default).await;
+
+    assert_eq!(posts, ["a", "b", "c", "d"]);
+}
+
+#[tokio::test]
+async fn offset_limit_with_total() {
+    let server = MockServer::start(|path, _| match path {
+        "/posts?sort=oldest&offset=0&limit=2" => forum_page(&["a", "b"], Some(5), None),
+        "/posts?sort=oldest&offset=2&limit=2" => forum_page(&["c", "d"], Some(5), None),
+        "/posts?sort=oldest&offset=4&limit=2" => forum_page(&["e"], Some(5), None),
+        _ => Response::status(404),
+    })
+    .await;
+
+    let posts = fetch_forum(&server, "offset-total", || OffsetLimit::new(2)).await;
+
+    assert_eq!(posts, ["a", "b", "c", "d", "e"]);
+}
+
+#[tokio::test]
+async fn offset_limit_until_short_page() {
+    let server = MockServer::start(|path, _| match path {
+        "/posts?sort=oldest&offset=0&limit=2" => forum_page(&["a", "b"], None, None),
+        "/posts?sort=oldest&offset=2&limit=2" => forum_page(&["c"], None, None),
+        _ => Response::status(404),
+    })
+    .await;
+
+    let posts = fetch_forum(&server, "offset-short", || OffsetLimit::new(2)).await;
+
+    assert_eq!(posts, ["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn fetch_all_skips_empty_texts() {
+    let server = MockServer::start(|path, _| {
+        let page = page_of(path).unwrap();
+
+        if path.starts_with("/projects") {
+            Response::json(projects_page(page, 2, 2))
+        } else if page == 1 {
+            Response::json(r#"{"devlogs": [{"id": 1, "body": "  "}, {"id": 2, "body": " hi "}], "pagination": {"current_page": 1, "total_pages": 1, "total_count": 2, "next_page": 2}}"#)
+        } else {
+            Response::status(404)
+        }
+    })
+    .await;
+
+    let documents = fetch_all_from(
+        &server.url,
+        "secret",
+        &cache("fetch-all", FetchMode::Fresh),
+        &limits(),
+    )
+    .await
+    .unwrap();
+
+    let texts: Vec<&str> = documents
+        .iter()
+        .map(|document| document.text.as_str())
+        .collect();
+
+    assert_eq!(
+        texts,
+        [
+            "projects 1-0",
+            "projects 1-1",
+            "projects 2-0",
+            "projects 2-1",
+            "hi"
+        ]
+    );
+    assert!(
+        server
+            .authorizations()
+            .iter()
+            .all(|auth| auth == "Bearer secret")
+    );
+}
+
+#[tokio::test]
+async fn fetch_all_keeps_metadata() {
+    let server = MockServer::start(|path, _| match path {
+        "/projects?page=1" => Response::json(
+            serde_json::json!({
+                "projects": [{
+                    "id": 7,
+                    "title": "Toaster",
+                    "description": "A toaster",
+                    "user_id": 42,
+                    "repo_url": "https://github.com/example/toaster",
+                    "devlog_ids": [3],
+                    "created_at": "2025-12-01T10:00:00.000Z",
+                }],
+                "pagination": { "current_page": 1, "total_pages": 1, "total_count": 1, "next_page": 2 },
+            })
+            .to_string(),
+        ),
+        "/devlogs?page=1" => Response::json(
+            serde_json::json!({
+                "devlogs": [{
+                    "id": 3,
+                    "body": "Made toast",
+                    "duration_seconds": 3600,
+                    "created_at": "2025-12-02T08:30:00Z",
+                }, {
+                    "id": 4,
+                    "body": "Orphan",
+                    "created_at": "yesterday",
+                }],
+                "pagination": { "current_page": 1, "total_pages": 1, "total_count": 2, "next_page": 2 },
+            })
+            .to_string(),
+        ),
+        _ => Response::status(404),
+    })
+    .await;
+
+    let documents = fetch_all_from(
+        &server.url,
+        "secret",
+        &cache("metadata", FetchMode::Fresh),
+        &limits(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        documents,
+        [
+            Document {
+                id: "project/7".into(),
+                source: Corpus::Flavortown,
+                kind: Some(Kind::Project),
+                author: Some("42".into()),
+                created_at: Some(datetime!(2025-12-01 10:00 UTC)),
+                project: Some("7".into()),
+                duration_seconds: None,
+                text: "A toaster".into(),
+                label: None,
+            },
+            // the project lists it, so it inherits the author
+            Document {
+                id: "devlog/3".into(),
+                source: Corpus::Flavortown,
+                kind: Some(Kind::Devlog),
+                author: Some("42".into()),
+                created_at: Some(datetime!(2025-12-02 8:30 UTC)),
+                project: Some("7".into()),
+                duration_seconds: Some(3600),
+                text: "Made toast".into(),
+                label: None,
+            },
+            Document {
+                id: "devlog/4".into(),
+                source: Corpus::Flavortown,
+                kind: Some(Kind::Devlog),
+                author: None,
+                created_at: None,
+                project: None,
+                duration_seconds: None,
+                text: "Orphan".into(),
+                label: None,
+            },
+        ]
+    );
+}
@@ -0,0 +1,53 @@
+use sonai_metrics::{LanguageModels, NgramModel, TextMetricFactory};
+
+use training::document::{Corpus, Document};
+use training::metrics::{calculate, metrics_version};
+use training::store::{Store, text_hash};
+
+#[test]
+fn metrics_are_computed_once_per_text_and_version() {
+    let factory = TextMetricFactory::new().unwrap();
+    let mut store = Store::open_in_memory().unwrap();
+    let documents = Document::from_texts(
+        vec![
+            "I built a thing 🚀 that delivers a seamless experience.".into(),
+            "fixed the bug lol, took forever".into(),
+        ],
+        Corpus::Flavortown,
+        None,
+    );
+    let version = metrics_version(&factory, None);
+
+    let computed = calculate(&factory, &mut store, &version, &documents).unwrap();
+
+    // what's stored is what was computed
+    let cached: sonai_metrics::TextMetrics = store
+        .metrics(&text_hash(&documents[0].text), &version)
+        .unwrap()
+        .unwrap();
+    assert_eq!(cached.emoji_rate, computed[0].emoji_rate);
+    assert_eq!(cached.buzzword_rate, computed[0].buzzword_rate);
+
+    let again = calculate(&factory, &mut store, &version, &documents).unwrap();
+    assert_eq!(format!("{:?}", again), format!("{:?}", computed));
+    assert_eq!(store.retain_metrics(&[&version]).unwrap(), 0);
+}
+
+#[test]
+fn language_models_change_the_version() {
+    let factory = TextMetricFactory::new().unwrap();
+    let version = metrics_version(&factory, None);
+
+    let language_models = LanguageModels {
+        human: NgramModel::train(["human text"], 3),
+        ai: NgramModel::train(["ai text"], 3),
+    };
+    let file =
+        bincode::serde::encode_to_vec(&language_models, bincode::config::standard()).unwrap();
+    let factory = factory.with_language_models(language_models);
+
+    let with_models = metrics_version(&factory, Some(&file));
+    assert_ne!(with_models, version);
+    assert!(with_models.starts_with(&version));
+    assert_eq!(metrics_version(&factory, Some(&file)), with_models);
+}
@@ -0,0 +1,200 @@
+//! A tiny HTTP server for the fetch tests, so they run offline and can script exactly what the API
+//! does. Every connection gets one response and is closed.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    delay: Duration,
+}
+
+impl Response {
+    pub fn json(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: String::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// 429 with `Retry-After: <seconds>`
+    pub fn too_many_requests(seconds: u64) -> Self {
+        Self::status(429).header("Retry-After", seconds)
+    }
+
+    /// 429 with `Retry-After: <http date>`
+    pub fn too_many_requests_until(until: SystemTime) -> Self {
+        Self::status(429).header("Retry-After", httpdate::fmt_http_date(until))
+    }
+
+    /// Sent after `delay`, to make responses arrive out of order
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// Pages of the Flavortown API, `per_page` items each and `total_pages` of them
+pub fn devlogs_page(page: u64, total_pages: u64, per_page: u64) -> String {
+    flavortown_page("devlogs", "body", page, total_pages, per_page)
+}
+
+pub fn projects_page(page: u64, total_pages: u64, per_page: u64) -> String {
+    flavortown_page("projects", "description", page, total_pages, per_page)
+}
+
+fn flavortown_page(kind: &str, field: &str, page: u64, total_pages: u64, per_page: u64) -> String {
+    let items: Vec<String> = (0..per_page)
+        .map(|i| {
+            format!(
+                r#"{{"id": {}, "{field}": "{kind} {page}-{i}"}}"#,
+                page * 1000 + i
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{{"{kind}": [{}], "pagination": {{"current_page": {page}, "total_pages": {total_pages}, "total_count": {}, "next_page": {}}}}}"#,
+        items.join(", "),
+        total_pages * per_page,
+        page + 1,
+    )
+}
+
+type Handler = dyn Fn(&str, usize) -> Response + Send + Sync;
+
+#[derive(Default)]
+struct Log {
+    requests: Vec<(String, String)>, // (path with query, authorization)
+    hits: HashMap<String, usize>,
+}
+
+pub struct MockServer {
+    pub url: String,
+    log: Arc<Mutex<Log>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// `handler` gets the path with the query and how often it was requested before
+    pub async fn start(handler: impl Fn(&str, usize) -> Response + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let log = Arc::new(Mutex::new(Log::default()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let task = tokio::spawn({
+            let log = log.clone();
+
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(respond(stream, handler.clone(), log.clone()));
+                }
+            }
+        });
+
+        Self { url, log, task }
+    }
+
+    /// Paths of every request so far, in arrival order
+    pub fn requests(&self) -> Vec<String> {
+        let log = self.log.lock().unwrap();
+        log.requests.iter().map(|(path, _)| path.clone()).collect()
+    }
+
+    pub fn authorizations(&self) -> Vec<String> {
+        let log = self.log.lock().unwrap();
+        log.requests.iter().map(|(_, auth)| auth.clone()).collect()
+    }
+
+    pub fn hits(&self, path: &str) -> usize {
+        self.log
+            .lock()
+            .unwrap()
+            .hits
+            .get(path)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn respond(mut stream: TcpStream, handler: Arc<Handler>, log: Arc<Mutex<Log>>) {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+
+    // GETs have no body, the head is all there is
+    while !request.ends_with(b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buffer[..n]),
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let path = request.split(' ').nth(1).unwrap_or("/").to_string();
+    let authorization = request
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("authorization")
+                .then(|| value.trim().to_string())
+        })
+        .unwrap_or_default();
+
+    let hits = {
+        let mut log = log.lock().unwrap();
+        log.requests.push((path.clone(), authorization));
+
+        let hits = log.hits.entry(path.clone()).or_default();
+        *hits += 1;
+        *hits - 1
+    };
+
+    let response = handler(&path, hits);
+    tokio::time::sleep(response.delay).await;
+
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(response.body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
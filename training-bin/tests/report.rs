@@ -0,0 +1,126 @@
+use time::OffsetDateTime;
+use time::macros::{date, datetime};
+
+use training::document::{Corpus, Document, Kind};
+use training::report::{Bucket, Series, Share, TimeSeries};
+
+mod common;
+
+fn document(source: Corpus, kind: Option<Kind>, created_at: Option<OffsetDateTime>) -> Document {
+    Document {
+        kind,
+        created_at,
+        ..common::document(source)
+    }
+}
+
+#[test]
+fn weeks_start_on_monday() {
+    // 2025-12-03 is a wednesday
+    assert_eq!(
+        Bucket::Week.start(date!(2025 - 12 - 03)),
+        date!(2025 - 12 - 01)
+    );
+    assert_eq!(
+        Bucket::Week.start(date!(2025 - 12 - 01)),
+        date!(2025 - 12 - 01)
+    );
+    assert_eq!(
+        Bucket::Week.start(date!(2025 - 12 - 07)),
+        date!(2025 - 12 - 01)
+    );
+    assert_eq!(
+        Bucket::Day.start(date!(2025 - 12 - 03)),
+        date!(2025 - 12 - 03)
+    );
+}
+
+#[test]
+fn shares_are_split_by_bucket_and_series() {
+    let documents = [
+        document(
+            Corpus::Flavortown,
+            Some(Kind::Devlog),
+            Some(datetime!(2025-12-01 10:00 UTC)),
+        ),
+        document(
+            Corpus::Flavortown,
+            Some(Kind::Project),
+            Some(datetime!(2025-12-03 10:00 UTC)),
+        ),
+        document(
+            Corpus::Flavortown,
+            Some(Kind::Devlog),
+            Some(datetime!(2025-12-08 10:00 UTC)),
+        ),
+        document(Corpus::Som, None, None),
+    ];
+    let ai = [true, false, false, true];
+
+    let weekly = TimeSeries::new(Bucket::Week, documents.iter().zip(ai));
+
+    assert_eq!(weekly.undated, 1);
+    assert_eq!(
+        weekly.dates(),
+        [date!(2025 - 12 - 01), date!(2025 - 12 - 08)]
+    );
+    assert_eq!(
+        weekly.shares[&(Series::All, date!(2025 - 12 - 01))],
+        Share { ai: 1, total: 2 }
+    );
+    assert_eq!(
+        weekly.shares[&(Series::Devlogs, date!(2025 - 12 - 01))],
+        Share { ai: 1, total: 1 }
+    );
+    assert_eq!(
+        weekly.shares[&(Series::Projects, date!(2025 - 12 - 01))],
+        Share { ai: 0, total: 1 }
+    );
+    assert!(!weekly.series().contains(&Series::Som));
+
+    assert_eq!(
+        weekly.csv(),
+        "week,series,ai,total,ai_percent
+2025-12-01,all,1,2,50.00
+2025-12-01,projects,0,1,0.00
+2025-12-01,devlogs,1,1,100.00
+2025-12-01,flavortown,1,2,50.00
+2025-12-08,all,0,1,0.00
+2025-12-08,devlogs,0,1,0.00
+2025-12-08,flavortown,0,1,0.00
+"
+    );
+}
+
+#[test]
+fn undated_documents_stay_out_of_their_series_buckets() {
+    let dated = Some(datetime!(2025-12-02 10:00 UTC));
+    let documents = [
+        document(Corpus::Flavortown, Some(Kind::Devlog), dated),
+        document(Corpus::Flavortown, Some(Kind::Devlog), None),
+        document(Corpus::Flavortown, Some(Kind::Devlog), dated),
+        document(Corpus::Flavortown, Some(Kind::Devlog), None),
+    ];
+    // both undated ones are AI, counting them would make the week all AI
+    let ai = [true, true, false, true];
+
+    let weekly = TimeSeries::new(Bucket::Week, documents.iter().zip(ai));
+
+    assert_eq!(weekly.undated, 2);
+    assert_eq!(weekly.dates(), [date!(2025 - 12 - 01)]);
+    for series in [Series::All, Series::Devlogs, Series::Flavortown] {
+        assert_eq!(
+            weekly.shares[&(series, date!(2025 - 12 - 01))],
+            Share { ai: 1, total: 2 }
+        );
+    }
+}
+
+#[test]
+fn nothing_dated_draws_nothing() {
+    let documents = [document(Corpus::Som, None, None)];
+    let daily = TimeSeries::new(Bucket::Day, documents.iter().zip([true]));
+
+    assert_eq!(daily.csv(), "day,series,ai,total,ai_percent\n");
+    assert_eq!(daily.svg(), "");
+}
@@ -0,0 +1,92 @@
+use training::document::{Corpus, Document};
+use training::review::{GroupBy, Prediction, csv, review_queue, wilson_interval};
+
+mod common;
+
+use common::document;
+
+fn post(author: &str, project: &str) -> Document {
+    Document {
+        author: Some(author.into()),
+        project: Some(project.into()),
+        ..document(Corpus::Flavortown)
+    }
+}
+
+fn predictions<'a>(
+    documents: &'a [Document],
+    flagged: &[bool],
+) -> impl Iterator<Item = Prediction<'a>> {
+    documents
+        .iter()
+        .zip(flagged.to_vec())
+        .map(|(document, flagged)| Prediction {
+            document,
+            probability: if flagged { 0.75 } else { 0.25 },
+            flagged,
+        })
+}
+
+#[test]
+fn wilson_interval_matches_the_formula() {
+    let (low, high) = wilson_interval(5, 10);
+    assert!((low - 0.2366).abs() < 1e-4, "{low}");
+    assert!((high - 0.7634).abs() < 1e-4, "{high}");
+
+    let (low, high) = wilson_interval(0, 20);
+    assert_eq!(low, 0.);
+    assert!(high > 0. && high < 0.2, "{high}");
+
+    assert_eq!(wilson_interval(0, 0), (0., 1.));
+}
+
+#[test]
+fn few_posts_are_left_out_and_many_flagged_posts_rank_first() {
+    let mut documents = vec![post("once", "a")];
+    let mut flagged = vec![true];
+
+    // 3 out of 3 is less certain than 40 out of 50
+    for _ in 0..3 {
+        documents.push(post("few", "b"));
+        flagged.push(true);
+    }
+
+    for i in 0..50 {
+        documents.push(post("many", "c"));
+        flagged.push(i < 40);
+    }
+
+    for _ in 0..5 {
+        documents.push(post("human", "c"));
+        flagged.push(false);
+    }
+
+    let queue = review_queue(GroupBy::Author, predictions(&documents, &flagged), 3);
+    let keys: Vec<&str> = queue.iter().map(|group| group.key.as_str()).collect();
+
+    assert_eq!(keys, ["many", "few", "human"]);
+    assert_eq!(queue[0].posts, 50);
+    assert_eq!(queue[0].flagged, 40);
+    assert!((queue[0].mean - 0.65).abs() < 1e-9);
+    assert_eq!(queue[0].max, 0.75);
+    assert_eq!(queue[2].flagged, 0);
+
+    let queue = review_queue(GroupBy::Project, predictions(&documents, &flagged), 3);
+    let keys: Vec<&str> = queue.iter().map(|group| group.key.as_str()).collect();
+
+    assert_eq!(keys, ["c", "b"]);
+    assert_eq!(queue[0].posts, 55);
+}
+
+#[test]
+fn csv_has_one_line_per_group() {
+    let documents = [post("someone", "a"), post("someone", "a")];
+    let queue = review_queue(GroupBy::Author, predictions(&documents, &[true, false]), 1);
+
+    assert_eq!(
+        csv(GroupBy::Author, &queue),
+        "author,posts,flagged,flagged_low,flagged_high,mean_ai,max_ai
+someone,2,1,0.0945,0.9055,0.5000,0.7500
+"
+    );
+}
@@ -0,0 +1,207 @@
+use std::fs;
+
+use bincode::config::standard;
+use bincode::serde::{decode_from_slice, encode_to_vec};
+use time::macros::datetime;
+
+use training::document::{Corpus, Document, Kind, Label};
+use training::store::{Store, text_hash};
+
+mod common;
+
+use common::document;
+
+fn devlog(id: u64, text: &str) -> Document {
+    Document {
+        id: format!("devlog/{id}"),
+        kind: Some(Kind::Devlog),
+        author: Some("7".into()),
+        created_at: Some(datetime!(2025-12-01 10:30 UTC)),
+        project: Some("3".into()),
+        duration_seconds: Some(900),
+        text: text.into(),
+        ..document(Corpus::Flavortown)
+    }
+}
+
+#[test]
+fn documents_round_trip_and_are_updated() {
+    let mut store = Store::open_in_memory().unwrap();
+    let mut labeled = devlog(2, "second");
+    labeled.label = Some(Label::Human);
+
+    store
+        .insert_documents(&[devlog(1, "first"), labeled.clone()])
+        .unwrap();
+    assert_eq!(
+        store.documents(Corpus::Flavortown).unwrap(),
+        [devlog(1, "first"), labeled.clone()]
+    );
+
+    // an edit keeps its place and the label
+    let mut edited = devlog(1, "first, edited");
+    edited.created_at = None;
+    store
+        .insert_documents(&[edited.clone(), devlog(2, "second")])
+        .unwrap();
+
+    assert_eq!(
+        store.documents(Corpus::Flavortown).unwrap(),
+        [edited, labeled]
+    );
+    assert_eq!(store.count(Corpus::Flavortown).unwrap(), 2);
+    assert_eq!(store.count(Corpus::Som).unwrap(), 0);
+}
+
+#[test]
+fn older_stores_get_new_columns() {
+    let path = std::env::temp_dir().join(format!("training-store-old-{}.db", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute_batch(
+            "CREATE TABLE documents (
+                source TEXT NOT NULL,
+                id TEXT NOT NULL,
+                kind TEXT,
+                author TEXT,
+                created_at TEXT,
+                project TEXT,
+                text TEXT NOT NULL,
+                text_hash TEXT NOT NULL,
+                PRIMARY KEY (source, id)
+            );
+            INSERT INTO documents (source, id, text, text_hash)
+            VALUES ('flavortown', 'devlog/1', 'old', '');",
+        )
+        .unwrap();
+
+    let mut store = Store::open(&path).unwrap();
+    assert_eq!(
+        store.documents(Corpus::Flavortown).unwrap()[0].duration_seconds,
+        None
+    );
+
+    store.insert_documents(&[devlog(1, "new")]).unwrap();
+    assert_eq!(
+        store.documents(Corpus::Flavortown).unwrap(),
+        [devlog(1, "new")]
+    );
+
+    drop(store);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn current_is_the_latest_run() {
+    let mut store = Store::open_in_memory().unwrap();
+    store
+        .insert_documents(&Document::from_texts(
+            vec!["imported".into()],
+            Corpus::Flavortown,
+            None,
+        ))
+        .unwrap();
+
+    assert_eq!(store.current(Corpus::Flavortown).unwrap().len(), 1);
+
+    let started_at = datetime!(2026-01-01 0:00 UTC);
+    let first = store
+        .record_run(
+            Corpus::Flavortown,
+            started_at,
+            &[devlog(1, "a"), devlog(2, "b")],
+        )
+        .unwrap();
+    let second = store
+        .record_run(Corpus::Flavortown, started_at, &[devlog(2, "b, edited")])
+        .unwrap();
+
+    assert_eq!(
+        store.current(Corpus::Flavortown).unwrap(),
+        [devlog(2, "b, edited")]
+    );
+    assert_eq!(
+        store.snapshot(first).unwrap(),
+        [devlog(1, "a"), devlog(2, "b, edited")]
+    );
+
+    let runs = store.runs().unwrap();
+    assert_eq!(
+        runs.iter()
+            .map(|run| (run.id, run.documents))
+            .collect::<Vec<_>>(),
+        [(first, 2), (second, 1)]
+    );
+    assert_eq!(runs[0].source, Corpus::Flavortown);
+    assert_eq!(runs[0].started_at, started_at);
+    assert_eq!(store.documents(Corpus::Flavortown).unwrap().len(), 3);
+}
+
+#[test]
+fn data_files_import_and_export() {
+    let dir = std::env::temp_dir().join(format!("training-store-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let texts = vec!["one".to_string(), "two".to_string()];
+    fs::write(
+        dir.join("ai.data"),
+        encode_to_vec(&texts, standard()).unwrap(),
+    )
+    .unwrap();
+
+    let mut store = Store::open(dir.join("corpus.db")).unwrap();
+    assert_eq!(
+        store
+            .import(dir.join("ai.data"), Corpus::Ai, Some(Label::Ai))
+            .unwrap(),
+        2
+    );
+
+    let documents = store.current(Corpus::Ai).unwrap();
+    assert_eq!(documents[1].id, "ai/1");
+    assert_eq!(documents[1].label, Some(Label::Ai));
+
+    assert_eq!(store.export(Corpus::Ai, dir.join("out.data")).unwrap(), 2);
+    let exported: Vec<String> =
+        decode_from_slice(&fs::read(dir.join("out.data")).unwrap(), standard())
+            .unwrap()
+            .0;
+    assert_eq!(exported, texts);
+
+    drop(store);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn metrics_are_keyed_by_hash_and_version() {
+    let mut store = Store::open_in_memory().unwrap();
+    let hash = text_hash("some text");
+
+    assert_eq!(hash, text_hash("some text"));
+    assert_ne!(hash, text_hash("some other text"));
+
+    store
+        .store_metrics("v1", [(hash.as_str(), &vec![1.5, 2.])])
+        .unwrap();
+    store
+        .store_metrics("v0", [(hash.as_str(), &vec![0.])])
+        .unwrap();
+
+    assert_eq!(
+        store.metrics::<Vec<f64>>(&hash, "v1").unwrap(),
+        Some(vec![1.5, 2.])
+    );
+    assert_eq!(store.metrics::<Vec<f64>>(&hash, "v2").unwrap(), None);
+    assert_eq!(
+        store
+            .metrics::<Vec<f64>>(&text_hash("some other text"), "v1")
+            .unwrap(),
+        None
+    );
+
+    // only the versions in use survive
+    assert_eq!(store.retain_metrics(&["v1"]).unwrap(), 1);
+    assert_eq!(store.metrics::<Vec<f64>>(&hash, "v0").unwrap(), None);
+    assert!(store.metrics::<Vec<f64>>(&hash, "v1").unwrap().is_some());
+}